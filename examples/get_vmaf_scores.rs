@@ -2,7 +2,7 @@ use indicatif::{ProgressBar, ProgressStyle};
 use libvmaf_rs::{
     model::{config::ModelConfig, Model},
     video::Video,
-    vmaf::{status::VmafStatus, Vmaf},
+    vmaf::{pooling::PoolingMethod, status::VmafStatus, Vmaf},
 };
 
 fn main() {
@@ -27,8 +27,14 @@ fn main() {
         .with_style(style);
 
     let callback = |status: VmafStatus| match status {
-        VmafStatus::Decode => decode_progress.inc(1),
-        VmafStatus::GetScore => get_score_progress.inc(1),
+        VmafStatus::Decode { index, .. } => decode_progress.set_position(index as u64 + 1),
+        VmafStatus::GetScore {
+            total: Some(total), ..
+        } => {
+            get_score_progress.set_length(total as u64);
+            get_score_progress.inc(1)
+        }
+        _ => (),
     };
 
     let scores = vmaf
@@ -38,7 +44,7 @@ fn main() {
     decode_progress.finish();
     get_score_progress.finish();
 
-    let average: f64 = scores.into_iter().sum::<f64>() / num_frames as f64;
+    let average: f64 = PoolingMethod::Mean.pool(&scores).unwrap();
 
     println!("Pooled VMAF Score: {average}");
 }
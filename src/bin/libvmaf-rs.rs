@@ -0,0 +1,400 @@
+//! A command line tool mirroring the upstream `vmaf` tool, built on top of `Vmaf`, `Video` and `Model`
+//!
+//! ```text
+//! libvmaf-rs --reference reference.y4m --distorted distorted.mkv --model vmaf_v0.6.1 --feature psnr --output report.json --format json
+//! ```
+
+use clap::{Parser, ValueEnum};
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use ffmpeg_next::{format::Pixel, media::Type};
+use libvmaf_rs::{
+    model::{builtin::BuiltinModel, config::ModelConfig, error::ModelError, Model},
+    video::{error::VideoError, Video},
+    vmaf::{config::VmafConfig, output::OutputFormat, pooling::PoolingMethod, status::VmafStatus},
+};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+enum CliError {
+    #[error("Couldn't open {0:?}")]
+    Input(PathBuf),
+    #[error("Raw YUV {0} at {1} bits isn't supported")]
+    RawFormat(&'static str, u8),
+    #[error("--width/--height required for raw input {0:?}")]
+    RawResolution(PathBuf),
+    #[error("Couldn't load model {0:?}")]
+    Model(String),
+    #[error("Couldn't compute VMAF scores")]
+    Vmaf,
+}
+
+/// Chroma subsampling of raw YUV inputs
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Subsampling {
+    #[value(name = "420")]
+    Yuv420,
+    #[value(name = "422")]
+    Yuv422,
+    #[value(name = "444")]
+    Yuv444,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Xml,
+    Json,
+    Csv,
+    Sub,
+}
+
+impl From<Format> for OutputFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Xml => OutputFormat::Xml,
+            Format::Json => OutputFormat::Json,
+            Format::Csv => OutputFormat::Csv,
+            Format::Sub => OutputFormat::Sub,
+        }
+    }
+}
+
+/// Compute VMAF scores of a distorted video against a reference video
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Reference video. Any file FFMPEG can read, including Y4M, or raw YUV with a `.yuv` extension
+    #[arg(short, long)]
+    reference: PathBuf,
+
+    /// Distorted video. Any file FFMPEG can read, including Y4M, or raw YUV with a `.yuv` extension
+    #[arg(short, long)]
+    distorted: PathBuf,
+
+    /// Width of raw YUV inputs. Other inputs are scaled to this width, and keep the width of the reference otherwise
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// Height of raw YUV inputs. Other inputs are scaled to this height, and keep the height of the reference otherwise
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Chroma subsampling of raw YUV inputs
+    #[arg(short, long, value_enum, default_value_t = Subsampling::Yuv420)]
+    pixel_format: Subsampling,
+
+    /// Bit depth of raw YUV inputs: 8, 9, 10, 12, 14 or 16
+    #[arg(short, long, default_value_t = 8)]
+    bitdepth: u8,
+
+    /// A built-in model version such as `vmaf_v0.6.1`, or the path to a JSON model. May be prefixed with `version=` or `path=`
+    #[arg(short, long, default_value = "vmaf_v0.6.1")]
+    model: String,
+
+    /// Additional feature extractor to run, such as `psnr`, `float_ssim` or `cambi`. May be given several times
+    #[arg(long)]
+    feature: Vec<String>,
+
+    /// Number of threads libvmaf uses for feature extraction
+    #[arg(long)]
+    threads: Option<u32>,
+
+    /// Only score every Nth frame
+    #[arg(long, default_value_t = 1)]
+    subsample: u32,
+
+    /// Write libvmaf's report of every metric to this file
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Format of the report written to `--output`
+    #[arg(long, value_enum, default_value_t = Format::Xml)]
+    format: Format,
+
+    /// Don't show progress
+    #[arg(short, long)]
+    quiet: bool,
+}
+
+impl Args {
+    /// The FFMPEG pixel format of raw YUV inputs
+    fn raw_format(&self) -> Result<Pixel, CliError> {
+        use Subsampling::{Yuv420, Yuv422, Yuv444};
+
+        Ok(match (self.pixel_format, self.bitdepth) {
+            (Yuv420, 8) => Pixel::YUV420P,
+            (Yuv420, 9) => Pixel::YUV420P9LE,
+            (Yuv420, 10) => Pixel::YUV420P10LE,
+            (Yuv420, 12) => Pixel::YUV420P12LE,
+            (Yuv420, 14) => Pixel::YUV420P14LE,
+            (Yuv420, 16) => Pixel::YUV420P16LE,
+            (Yuv422, 8) => Pixel::YUV422P,
+            (Yuv422, 9) => Pixel::YUV422P9LE,
+            (Yuv422, 10) => Pixel::YUV422P10LE,
+            (Yuv422, 12) => Pixel::YUV422P12LE,
+            (Yuv422, 14) => Pixel::YUV422P14LE,
+            (Yuv422, 16) => Pixel::YUV422P16LE,
+            (Yuv444, 8) => Pixel::YUV444P,
+            (Yuv444, 9) => Pixel::YUV444P9LE,
+            (Yuv444, 10) => Pixel::YUV444P10LE,
+            (Yuv444, 12) => Pixel::YUV444P12LE,
+            (Yuv444, 14) => Pixel::YUV444P14LE,
+            (Yuv444, 16) => Pixel::YUV444P16LE,
+            (subsampling, bitdepth) => {
+                let name = match subsampling {
+                    Yuv420 => "4:2:0",
+                    Yuv422 => "4:2:2",
+                    Yuv444 => "4:4:4",
+                };
+                return Err(Report::new(CliError::RawFormat(name, bitdepth)));
+            }
+        })
+    }
+
+    /// Resolution both inputs are compared at. Raw YUV has no header to probe, so it has to be given
+    fn resolution(&self) -> Result<(u32, u32), CliError> {
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => Ok((width, height)),
+            _ if is_raw(&self.reference) => {
+                Err(Report::new(CliError::RawResolution(self.reference.clone())))
+            }
+            _ if is_raw(&self.distorted) => {
+                Err(Report::new(CliError::RawResolution(self.distorted.clone())))
+            }
+            (width, height) => {
+                let (native_width, native_height) = probe_resolution(&self.reference)
+                    .change_context(CliError::Input(self.reference.clone()))?;
+                Ok((
+                    width.unwrap_or(native_width),
+                    height.unwrap_or(native_height),
+                ))
+            }
+        }
+    }
+
+    fn open(&self, path: &Path, width: u32, height: u32) -> Result<Video, CliError> {
+        let video = if is_raw(path) {
+            Video::new_raw(path, self.raw_format()?, width, height)
+        } else {
+            Video::new(path, width, height)
+        };
+
+        video.change_context(CliError::Input(path.to_path_buf()))
+    }
+
+    /// Where `--model` says to load the model from
+    fn model_source(&self) -> ModelSource {
+        let model = self.model.as_str();
+
+        if let Some(version) = model.strip_prefix("version=") {
+            return ModelSource::Version(version);
+        }
+
+        if let Some(path) = model.strip_prefix("path=") {
+            return ModelSource::Path(Path::new(path));
+        }
+
+        if model.ends_with(".json") || Path::new(model).is_file() {
+            ModelSource::Path(Path::new(model))
+        } else {
+            ModelSource::Version(model)
+        }
+    }
+
+    fn load_model(&self) -> Result<Model, CliError> {
+        let model = match self.model_source() {
+            ModelSource::Version(version) => load_version(version),
+            ModelSource::Path(path) => Model::load_model(ModelConfig::default(), path),
+        };
+
+        model.change_context_lazy(|| CliError::Model(self.model.clone()))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ModelSource<'a> {
+    /// A model compiled into libvmaf
+    Version(&'a str),
+    /// A JSON model on disk
+    Path(&'a Path),
+}
+
+/// Collections have to be loaded as such, and anything which isn't a known built-in model is left for libvmaf to look up
+fn load_version(version: &str) -> Result<Model, ModelError> {
+    match version.parse::<BuiltinModel>() {
+        Ok(builtin) => Model::builtin(ModelConfig::default(), builtin),
+        Err(_) => Model::new(ModelConfig::default(), version.to_string()),
+    }
+}
+
+fn is_raw(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("yuv"))
+}
+
+/// Width and height of the best video stream of `path`, before any scaling
+fn probe_resolution(path: &Path) -> Result<(u32, u32), VideoError> {
+    let error = || VideoError::Construct(path.to_path_buf());
+
+    ffmpeg_next::init()
+        .into_report()
+        .change_context_lazy(error)?;
+
+    let input = ffmpeg_next::format::input(&path)
+        .into_report()
+        .change_context_lazy(error)?;
+
+    let stream = input
+        .streams()
+        .best(Type::Video)
+        .ok_or_else(|| Report::new(error()))?;
+
+    let decoder = ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())
+        .and_then(|context| context.decoder().video())
+        .into_report()
+        .change_context_lazy(error)?;
+
+    Ok((decoder.width(), decoder.height()))
+}
+
+fn main() -> Result<(), CliError> {
+    let args = Args::parse();
+
+    let (width, height) = args.resolution()?;
+    let reference = args.open(&args.reference, width, height)?;
+    let distorted = args.open(&args.distorted, width, height)?;
+    let model = args.load_model()?;
+    let name = model.version().unwrap_or_else(|| "vmaf".to_string());
+
+    let mut config = VmafConfig::new().n_subsample(args.subsample);
+    if let Some(threads) = args.threads {
+        config = config.n_threads(threads);
+    }
+
+    let mut vmaf = config.build().change_context(CliError::Vmaf)?;
+
+    for feature in &args.feature {
+        vmaf.use_feature(feature).change_context(CliError::Vmaf)?;
+    }
+
+    if let Some(output) = &args.output {
+        vmaf = vmaf.with_output(output, args.format.into());
+    }
+
+    let quiet = args.quiet;
+    let progress = move |status: VmafStatus| {
+        if quiet {
+            return;
+        }
+
+        let mut stderr = std::io::stderr();
+        let _ = match status {
+            VmafStatus::Decode {
+                index,
+                total: Some(total),
+                ..
+            } => write!(stderr, "\rReading frame {}/{total}", index + 1),
+            VmafStatus::Decode { index, .. } => write!(stderr, "\rReading frame {}", index + 1),
+            VmafStatus::Done { scored, elapsed } => {
+                writeln!(stderr, "\nScored {scored} frames in {elapsed:.2?}")
+            }
+            _ => Ok(()),
+        };
+    };
+
+    let scores = vmaf
+        .get_vmaf_scores(reference, distorted, model, Some(progress))
+        .change_context(CliError::Vmaf)?;
+
+    if let Some(mean) = PoolingMethod::Mean.pool(&scores) {
+        println!("{name}: {mean:.6}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Args, CliError, ModelSource};
+    use clap::Parser;
+    use ffmpeg_next::format::Pixel;
+    use std::path::Path;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::try_parse_from(
+            ["libvmaf-rs", "-r", "reference.y4m", "-d", "distorted.y4m"]
+                .iter()
+                .chain(args),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn model_source() {
+        assert_eq!(
+            parse(&[]).model_source(),
+            ModelSource::Version("vmaf_v0.6.1")
+        );
+        assert_eq!(
+            parse(&["--model", "version=vmaf_b_v0.6.3"]).model_source(),
+            ModelSource::Version("vmaf_b_v0.6.3")
+        );
+        assert_eq!(
+            parse(&["--model", "path=models/custom"]).model_source(),
+            ModelSource::Path(Path::new("models/custom"))
+        );
+        assert_eq!(
+            parse(&["-m", "custom.json"]).model_source(),
+            ModelSource::Path(Path::new("custom.json"))
+        );
+    }
+
+    #[test]
+    fn features() {
+        let args = parse(&["--feature", "psnr", "--feature", "float_ssim"]);
+        assert_eq!(args.feature, ["psnr", "float_ssim"]);
+    }
+
+    #[test]
+    fn raw() {
+        let args = Args::try_parse_from([
+            "libvmaf-rs",
+            "-r",
+            "reference.yuv",
+            "-d",
+            "distorted.yuv",
+            "--width",
+            "1920",
+        ])
+        .unwrap();
+
+        let err = args.resolution().unwrap_err();
+        assert!(matches!(
+            err.current_context(),
+            CliError::RawResolution(path) if path == Path::new("reference.yuv")
+        ));
+
+        let args = Args::try_parse_from([
+            "libvmaf-rs",
+            "-r",
+            "reference.yuv",
+            "-d",
+            "distorted.yuv",
+            "--width",
+            "1920",
+            "--height",
+            "1080",
+            "--bitdepth",
+            "14",
+            "--pixel-format",
+            "422",
+        ])
+        .unwrap();
+
+        assert_eq!(args.resolution().unwrap(), (1920, 1080));
+        assert_eq!(args.raw_format().unwrap(), Pixel::YUV422P14LE);
+    }
+}
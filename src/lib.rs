@@ -2,6 +2,12 @@
 //!
 //! VMAF is an Emmy-winning perceptual video quality assessment algorithm developed by Netflix. It is a full-reference metric, meaning that it
 //! is calculated on pairs of reference/distorted pictures
+//!
+//! Enable the `tracing` feature to have score calculation emit [`tracing`](https://docs.rs/tracing) spans and events
+//!
+//! Enable the `async` feature to run score calculation on tokio's blocking thread pool with `Vmaf::score_stream()`
+//!
+//! Enable the `cli` feature to build the `libvmaf-rs` binary, a command line tool mirroring the upstream `vmaf` tool
 
 /// This module defines a wrapper around VmafPicture. This module also translates between FFMPEG's AVFrame struct and VmafPicture
 pub mod picture;
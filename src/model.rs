@@ -1,17 +1,26 @@
-use error_stack::{Result, ResultExt};
-use libvmaf_sys::{vmaf_model_destroy, vmaf_model_load, vmaf_model_load_from_path, VmafModel};
+use error_stack::{IntoReport, Result, ResultExt};
+use libvmaf_sys::{
+    vmaf_feature_dictionary_free, vmaf_feature_dictionary_set, vmaf_model_collection_destroy,
+    vmaf_model_collection_load, vmaf_model_destroy, vmaf_model_feature_overload, vmaf_model_load,
+    vmaf_model_load_from_path, VmafFeatureDictionary, VmafModel, VmafModelCollection,
+};
 use ptrplus::{AsPtr, IntoRaw};
 use std::{
     ffi::{c_char, CString, OsStr},
     fmt::Display,
+    io::Write,
     path::{Path, PathBuf},
 };
 
 use crate::error::FFIError;
 
-use self::{config::ModelConfig, error::ModelError};
+use self::{
+    builtin::BuiltinModel, config::ModelConfig, description::ModelDescription, error::ModelError,
+};
 
+pub mod builtin;
 pub mod config;
+pub mod description;
 pub mod error;
 
 #[derive(Debug)]
@@ -32,6 +41,37 @@ impl Model {
         Ok(Model(ptr, Some(version)))
     }
 
+    /// Load a model compiled into libvmaf. Unlike [`Model::new`], the version can't be misspelled.
+    ///
+    /// For a collection such as [`BuiltinModel::B0_6_3`], only the main model of the collection is kept
+    pub fn builtin(config: ModelConfig, model: BuiltinModel) -> Result<Model, ModelError> {
+        if !model.is_collection() {
+            return Model::new(config, model.version().to_string());
+        }
+
+        let mut ptr: *mut VmafModel = std::ptr::null_mut();
+        let mut collection: *mut VmafModelCollection = std::ptr::null_mut();
+
+        let mut config = config.as_ref().to_owned();
+
+        let version_cstring: CString = CString::new(model.version()).unwrap();
+        let err = unsafe {
+            vmaf_model_collection_load(
+                &mut ptr,
+                &mut collection,
+                &mut config,
+                version_cstring.as_ptr(),
+            )
+        };
+
+        FFIError::check_err(err).change_context(ModelError::Load(model.version().to_string()))?;
+
+        // The main model is allocated separately from the rest of the collection
+        unsafe { vmaf_model_collection_destroy(collection) };
+
+        Ok(Model(ptr, Some(model.version().to_string())))
+    }
+
     pub fn version(&self) -> Option<String> {
         self.1.clone()
     }
@@ -51,8 +91,108 @@ impl Model {
 
         Ok(Model(ptr, None))
     }
+
+    /// Load a model from JSON in the upstream format, such as a model embedded with `include_str!()`.
+    ///
+    /// The JSON is checked before libvmaf sees it, so a malformed model gives a [`ModelError::Invalid`] saying what is wrong.
+    /// libvmaf can only load models from files, so the JSON is written to a temporary file which is removed once loaded
+    pub fn from_json_str(config: ModelConfig, json: &str) -> Result<Model, ModelError> {
+        ModelDescription::from_json_str(json)?;
+
+        let mut file = tempfile::Builder::new()
+            .suffix(".json")
+            .tempfile()
+            .into_report()
+            .change_context(ModelError::TempFile)?;
+
+        file.write_all(json.as_bytes())
+            .and_then(|_| file.flush())
+            .into_report()
+            .change_context(ModelError::TempFile)?;
+
+        Model::load_model(config, file.path())
+    }
+
+    /// Load a model from the bytes of a JSON model in the upstream format. See [`Model::from_json_str`]
+    pub fn from_bytes(config: ModelConfig, bytes: &[u8]) -> Result<Model, ModelError> {
+        let json = std::str::from_utf8(bytes)
+            .into_report()
+            .change_context_lazy(|| ModelError::Invalid("not valid UTF-8".to_string()))?;
+
+        Model::from_json_str(config, json)
+    }
+
+    /// Override options of the feature extractor `feature_name`, such as `adm` or `float_vif`, for every feature of this model it computes.
+    /// Extractors the model doesn't use are left alone
+    /// ```ignore
+    /// let model = model.with_feature_overload("adm", [("adm_enhn_gain_limit", 1.0)])?;
+    /// ```
+    pub fn with_feature_overload<K: AsRef<str>, V: ToString>(
+        self,
+        feature_name: &str,
+        options: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Model, ModelError> {
+        let error = || ModelError::Overload(feature_name.to_string());
+
+        let name = CString::new(feature_name)
+            .into_report()
+            .change_context_lazy(error)?;
+        let mut dict: *mut VmafFeatureDictionary = std::ptr::null_mut();
+
+        let mut set = |key: K, value: V| -> Result<(), ModelError> {
+            let key = CString::new(key.as_ref())
+                .into_report()
+                .change_context_lazy(error)?;
+            let value = CString::new(value.to_string())
+                .into_report()
+                .change_context_lazy(error)?;
+
+            let err =
+                unsafe { vmaf_feature_dictionary_set(&mut dict, key.as_ptr(), value.as_ptr()) };
+            FFIError::check_err(err).change_context_lazy(error)
+        };
+
+        let result = options
+            .into_iter()
+            .try_for_each(|(key, value)| set(key, value));
+
+        if let Err(e) = result {
+            unsafe { vmaf_feature_dictionary_free(&mut dict) };
+            return Err(e);
+        }
+
+        // Nothing to overload
+        if dict.is_null() {
+            return Ok(self);
+        }
+
+        // libvmaf takes ownership of the dictionary, whether or not this succeeds
+        let err = unsafe { vmaf_model_feature_overload(self.0, name.as_ptr(), dict) };
+        FFIError::check_err(err).change_context_lazy(error)?;
+
+        Ok(self)
+    }
+
+    /// The NEG variant of this model, which doesn't reward enhancement such as sharpening.
+    /// This limits the enhancement gain of the ADM and VIF extractors, the same way the built-in `neg` models are made
+    pub fn neg(self) -> Result<Model, ModelError> {
+        [
+            ("adm", "adm_enhn_gain_limit"),
+            ("float_adm", "adm_enhn_gain_limit"),
+            ("vif", "vif_enhn_gain_limit"),
+            ("float_vif", "vif_enhn_gain_limit"),
+        ]
+        .into_iter()
+        .try_fold(self, |model, (extractor, option)| {
+            model.with_feature_overload(extractor, [(option, 1.0)])
+        })
+    }
 }
 
+// A VmafModel is plain heap data which is only read once loaded, so it may move between threads.
+// Overloading features mutates it, so Model isn't Sync
+unsafe impl Send for Model {}
+
 impl Display for Model {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self.1)
@@ -88,11 +228,34 @@ impl Drop for Model {
 #[cfg(test)]
 mod test {
 
-    use super::{config::ModelConfig, Model};
+    use super::{config::ModelConfig, error::ModelError, Model};
 
     #[test]
     fn construct() {
         let config = ModelConfig::default();
         let _model: Model = Model::new(config, "vmaf_v0.6.1".to_string()).unwrap();
     }
+
+    #[test]
+    fn from_json() {
+        let json = include_str!("../examples/vmaf_v0.6.1.json");
+        let _model: Model = Model::from_json_str(ModelConfig::default(), json).unwrap();
+        let _model: Model = Model::from_bytes(ModelConfig::default(), json.as_bytes()).unwrap();
+
+        let err =
+            Model::from_json_str(ModelConfig::default(), r#"{"param_dict": {}}"#).unwrap_err();
+        assert!(matches!(err.current_context(), ModelError::Invalid(_)));
+    }
+
+    #[test]
+    fn feature_overload() {
+        let model: Model = Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+        let model = model
+            .with_feature_overload("adm", [("adm_enhn_gain_limit", 1.0)])
+            .unwrap()
+            .neg()
+            .unwrap();
+
+        assert_eq!(model.version(), Some("vmaf_v0.6.1".to_string()));
+    }
 }
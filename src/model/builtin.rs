@@ -0,0 +1,136 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{config::ModelConfig, error::ModelError, Model};
+
+/// Versions of the models which may be compiled into libvmaf
+///
+/// Which of these are actually available depends on how libvmaf was built. Use [`BuiltinModel::available`] to find out at runtime.
+/// The floating point variants are only listed with the `float` feature enabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinModel {
+    /// `vmaf_v0.6.1`, the default model, trained for 1080p viewed on a TV
+    V0_6_1,
+    /// `vmaf_v0.6.1neg`, which doesn't reward enhancement such as sharpening
+    V0_6_1Neg,
+    /// `vmaf_4k_v0.6.1`, trained for 4K viewed on a TV
+    V4k0_6_1,
+    /// `vmaf_4k_v0.6.1neg`
+    V4k0_6_1Neg,
+    /// `vmaf_b_v0.6.3`, a collection of bootstrapped models. Only the main model of the collection is used for scoring
+    B0_6_3,
+    /// `vmaf_float_v0.6.1`
+    #[cfg(feature = "float")]
+    Float0_6_1,
+    /// `vmaf_float_v0.6.1neg`
+    #[cfg(feature = "float")]
+    Float0_6_1Neg,
+    /// `vmaf_float_4k_v0.6.1`
+    #[cfg(feature = "float")]
+    Float4k0_6_1,
+    /// `vmaf_float_b_v0.6.3`, a collection of bootstrapped models. Only the main model of the collection is used for scoring
+    #[cfg(feature = "float")]
+    FloatB0_6_3,
+}
+
+impl BuiltinModel {
+    /// Every model libvmaf may have been built with
+    pub const ALL: &'static [BuiltinModel] = &[
+        BuiltinModel::V0_6_1,
+        BuiltinModel::V0_6_1Neg,
+        BuiltinModel::V4k0_6_1,
+        BuiltinModel::V4k0_6_1Neg,
+        BuiltinModel::B0_6_3,
+        #[cfg(feature = "float")]
+        BuiltinModel::Float0_6_1,
+        #[cfg(feature = "float")]
+        BuiltinModel::Float0_6_1Neg,
+        #[cfg(feature = "float")]
+        BuiltinModel::Float4k0_6_1,
+        #[cfg(feature = "float")]
+        BuiltinModel::FloatB0_6_3,
+    ];
+
+    /// The version string libvmaf knows this model by
+    pub fn version(&self) -> &'static str {
+        match self {
+            BuiltinModel::V0_6_1 => "vmaf_v0.6.1",
+            BuiltinModel::V0_6_1Neg => "vmaf_v0.6.1neg",
+            BuiltinModel::V4k0_6_1 => "vmaf_4k_v0.6.1",
+            BuiltinModel::V4k0_6_1Neg => "vmaf_4k_v0.6.1neg",
+            BuiltinModel::B0_6_3 => "vmaf_b_v0.6.3",
+            #[cfg(feature = "float")]
+            BuiltinModel::Float0_6_1 => "vmaf_float_v0.6.1",
+            #[cfg(feature = "float")]
+            BuiltinModel::Float0_6_1Neg => "vmaf_float_v0.6.1neg",
+            #[cfg(feature = "float")]
+            BuiltinModel::Float4k0_6_1 => "vmaf_float_4k_v0.6.1",
+            #[cfg(feature = "float")]
+            BuiltinModel::FloatB0_6_3 => "vmaf_float_b_v0.6.3",
+        }
+    }
+
+    /// Returns true if this is a collection of models rather than a single model
+    pub fn is_collection(&self) -> bool {
+        match self {
+            BuiltinModel::B0_6_3 => true,
+            #[cfg(feature = "float")]
+            BuiltinModel::FloatB0_6_3 => true,
+            _ => false,
+        }
+    }
+
+    /// The models which were compiled into the libvmaf this crate is linked against.
+    /// libvmaf has no way of listing them, so this tries to load every model in [`BuiltinModel::ALL`]
+    pub fn available() -> Vec<BuiltinModel> {
+        BuiltinModel::ALL
+            .iter()
+            .copied()
+            .filter(|model| Model::builtin(ModelConfig::default(), *model).is_ok())
+            .collect()
+    }
+}
+
+impl Display for BuiltinModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.version())
+    }
+}
+
+impl FromStr for BuiltinModel {
+    type Err = ModelError;
+
+    /// Look up a built-in model by its version string, such as `vmaf_v0.6.1`
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        BuiltinModel::ALL
+            .iter()
+            .copied()
+            .find(|model| model.version() == version)
+            .ok_or_else(|| ModelError::Unknown(version.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BuiltinModel;
+    use crate::model::{config::ModelConfig, error::ModelError, Model};
+
+    #[test]
+    fn builtin() {
+        let model = Model::builtin(ModelConfig::default(), BuiltinModel::V0_6_1).unwrap();
+        assert_eq!(model.version(), Some("vmaf_v0.6.1".to_string()));
+
+        assert!(BuiltinModel::available().contains(&BuiltinModel::V0_6_1));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "vmaf_4k_v0.6.1".parse::<BuiltinModel>().unwrap(),
+            BuiltinModel::V4k0_6_1
+        );
+        assert!(matches!(
+            "vmaf_v0.61".parse::<BuiltinModel>(),
+            Err(ModelError::Unknown(_))
+        ));
+    }
+}
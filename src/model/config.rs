@@ -1,28 +1,76 @@
 use std::ffi::{CString, NulError};
 
+use bitflags::bitflags;
 use libvmaf_sys::{VmafModelConfig, VmafModelFlags};
 
-pub struct ModelConfig(VmafModelConfig);
+bitflags! {
+    /// Options changing how a model turns features into scores. These are the bits of libvmaf's `VmafModelFlags`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ModelFlags: u64 {
+        const DEFAULT = VmafModelFlags::VMAF_MODEL_FLAGS_DEFAULT as u64;
+        /// Don't clip scores to the model's `score_clip`, so scores may go above 100
+        const DISABLE_CLIP = VmafModelFlags::VMAF_MODEL_FLAG_DISABLE_CLIP as u64;
+        /// Apply the model's `score_transform`
+        const ENABLE_TRANSFORM = VmafModelFlags::VMAF_MODEL_FLAG_ENABLE_TRANSFORM as u64;
+        /// Don't use temporal features such as motion
+        const DISABLE_TEMPORAL = VmafModelFlags::VMAF_MODEL_FLAG_DISABLE_TEMPORAL as u64;
+    }
+}
+
+/// Configuration a model is loaded with
+///
+/// The name is owned by the configuration, and freed along with it
+pub struct ModelConfig(VmafModelConfig, Option<CString>);
 
 impl ModelConfig {
-    pub fn new<'a, N: AsRef<&'a str>>(
-        name: N,
-        flags: VmafModelFlags,
-    ) -> Result<ModelConfig, NulError> {
-        let name = CString::new(*name.as_ref())?.into_raw();
-        Ok(ModelConfig(VmafModelConfig {
-            name,
-            flags: flags as u64,
-        }))
+    /// A configuration naming the model `name`. libvmaf uses the name in its output reports,
+    /// and to tell the scores of several models apart
+    pub fn new(name: impl Into<String>, flags: ModelFlags) -> Result<ModelConfig, NulError> {
+        let name = CString::new(name.into())?;
+        Ok(ModelConfig(
+            VmafModelConfig {
+                // The string's buffer stays where it is when the configuration moves
+                name: name.as_ptr(),
+                flags: flags.bits(),
+            },
+            Some(name),
+        ))
+    }
+
+    /// An unnamed configuration with `flags`
+    pub fn with_flags(flags: ModelFlags) -> ModelConfig {
+        ModelConfig(
+            VmafModelConfig {
+                name: std::ptr::null(),
+                flags: flags.bits(),
+            },
+            None,
+        )
+    }
+
+    /// The phone model, which applies the model's score transform to predict quality on a phone screen
+    pub fn phone() -> ModelConfig {
+        ModelConfig::with_flags(ModelFlags::ENABLE_TRANSFORM)
+    }
+
+    /// Scores which aren't clipped, so that differences between high quality encodes above 100 aren't lost
+    pub fn no_clip() -> ModelConfig {
+        ModelConfig::with_flags(ModelFlags::DISABLE_CLIP)
+    }
+
+    /// The name given to [`ModelConfig::new`]
+    pub fn name(&self) -> Option<&str> {
+        self.1.as_deref().and_then(|name| name.to_str().ok())
+    }
+
+    pub fn flags(&self) -> ModelFlags {
+        ModelFlags::from_bits_truncate(self.0.flags)
     }
 }
 
 impl Default for ModelConfig {
     fn default() -> Self {
-        ModelConfig(VmafModelConfig {
-            name: std::ptr::null(),
-            flags: VmafModelFlags::VMAF_MODEL_FLAGS_DEFAULT as u64,
-        })
+        ModelConfig::with_flags(ModelFlags::DEFAULT)
     }
 }
 
@@ -31,3 +79,34 @@ impl AsRef<VmafModelConfig> for ModelConfig {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ModelConfig, ModelFlags};
+    use crate::model::Model;
+
+    #[test]
+    fn flags() {
+        assert_eq!(ModelConfig::default().flags(), ModelFlags::DEFAULT);
+        assert_eq!(
+            ModelConfig::with_flags(ModelFlags::ENABLE_TRANSFORM | ModelFlags::DISABLE_CLIP)
+                .flags(),
+            ModelFlags::ENABLE_TRANSFORM | ModelFlags::DISABLE_CLIP
+        );
+
+        let _model: Model = Model::new(ModelConfig::phone(), "vmaf_v0.6.1".to_string()).unwrap();
+        let _model: Model = Model::new(ModelConfig::no_clip(), "vmaf_v0.6.1".to_string()).unwrap();
+    }
+
+    #[test]
+    fn name() {
+        let config = ModelConfig::new("vmaf_phone", ModelFlags::ENABLE_TRANSFORM).unwrap();
+        assert_eq!(config.name(), Some("vmaf_phone"));
+        assert_eq!(config.flags(), ModelFlags::ENABLE_TRANSFORM);
+        assert_eq!(ModelConfig::default().name(), None);
+
+        assert!(ModelConfig::new("vmaf\0phone", ModelFlags::DEFAULT).is_err());
+
+        let _model: Model = Model::new(config, "vmaf_v0.6.1".to_string()).unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+use std::{collections::BTreeMap, path::Path};
+
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use serde::{Deserialize, Deserializer};
+
+use super::error::ModelError;
+
+/// What a model in the upstream JSON format needs and does, read without handing it to libvmaf
+/// ```ignore
+/// let description = ModelDescription::from_path("vmaf_float_v0.6.1.json")?;
+/// if !description.is_supported() {
+///     println!("{:?} needs libvmaf built with the float feature", description.feature_names);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ModelDescription {
+    /// Type of the SVM, and whether it is a bootstrapped (bagging) collection
+    pub model_type: ModelType,
+    /// Names of the features the model is evaluated on, in the order the model expects them
+    pub feature_names: Vec<String>,
+    /// Feature extractors the model was trained with, and the features each of them provides
+    #[serde(default, rename = "feature_dict")]
+    pub extractors: BTreeMap<String, Vec<String>>,
+    /// How features are normalized before being passed to the SVM
+    #[serde(default)]
+    pub norm_type: NormType,
+    /// Lower and upper bounds scores are clipped to, unless clipping is disabled in the `ModelConfig`
+    pub score_clip: Option<(f64, f64)>,
+    /// Polynomial applied to scores, if transforms are enabled in the `ModelConfig`
+    pub score_transform: Option<ScoreTransform>,
+    /// Slopes of the linear rescale, for the score followed by each feature
+    #[serde(default)]
+    pub slopes: Vec<f64>,
+    /// Intercepts of the linear rescale, for the score followed by each feature
+    #[serde(default)]
+    pub intercepts: Vec<f64>,
+}
+
+/// Types of model libvmaf can evaluate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ModelType {
+    /// A single nu-SVR model
+    #[serde(rename = "LIBSVMNUSVR")]
+    LibSvmNuSvr,
+    /// A collection of nu-SVR models trained on bootstrapped samples
+    #[serde(rename = "BOOTSTRAP_LIBSVMNUSVR")]
+    BootstrapLibSvmNuSvr,
+    /// A collection of nu-SVR models trained on the residue of bootstrapped samples
+    #[serde(rename = "RESIDUEBOOTSTRAP_LIBSVMNUSVR")]
+    ResidueBootstrapLibSvmNuSvr,
+}
+
+impl ModelType {
+    /// Returns true if the model is a collection, which has to be loaded as such to get confidence intervals
+    pub fn is_bootstrap(&self) -> bool {
+        !matches!(self, ModelType::LibSvmNuSvr)
+    }
+}
+
+/// Normalization applied to features before evaluation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormType {
+    #[default]
+    None,
+    /// Features are rescaled with [`ModelDescription::slopes`] and [`ModelDescription::intercepts`]
+    LinearRescale,
+}
+
+/// The score transform `p0 + p1 * score + p2 * score²`, which may be followed by a piecewise linear mapping through `knots`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScoreTransform {
+    pub p0: Option<f64>,
+    pub p1: Option<f64>,
+    pub p2: Option<f64>,
+    pub knots: Option<Vec<(f64, f64)>>,
+    /// Transformed scores are never above the original score
+    #[serde(default, deserialize_with = "string_bool")]
+    pub out_lte_in: bool,
+    /// Transformed scores are never below the original score
+    #[serde(default, deserialize_with = "string_bool")]
+    pub out_gte_in: bool,
+}
+
+/// Upstream models write their booleans as strings
+fn string_bool<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        String(String),
+    }
+
+    Ok(match Flag::deserialize(deserializer)? {
+        Flag::Bool(flag) => flag,
+        Flag::String(flag) => flag.eq_ignore_ascii_case("true"),
+    })
+}
+
+#[derive(Deserialize)]
+struct ModelFile {
+    model_dict: ModelDescription,
+}
+
+/// Prefix of the features computed by the floating point extractors, such as `VMAF_feature_adm2_score`
+const FLOAT_PREFIX: &str = "VMAF_feature";
+
+impl ModelDescription {
+    /// Parse a model in the upstream JSON format. Fails with [`ModelError::Invalid`] saying what is wrong with the model
+    pub fn from_json_str(json: &str) -> Result<ModelDescription, ModelError> {
+        let description = serde_json::from_str::<ModelFile>(json)
+            .map_err(|e| ModelError::Invalid(e.to_string()))?
+            .model_dict;
+
+        description.validate()?;
+        Ok(description)
+    }
+
+    /// Read and parse a model in the upstream JSON format
+    pub fn from_path(path: impl AsRef<Path>) -> Result<ModelDescription, ModelError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .into_report()
+            .change_context_lazy(|| ModelError::Path(Box::new(path.to_path_buf())))?;
+
+        ModelDescription::from_json_str(&json)
+            .attach_printable_lazy(|| format!("Couldn't parse model {path:?}"))
+    }
+
+    /// Returns true if any of the features need the floating point feature extractors
+    pub fn requires_float(&self) -> bool {
+        self.feature_names
+            .iter()
+            .chain(self.extractors.keys())
+            .any(|name| name.starts_with(FLOAT_PREFIX))
+    }
+
+    /// Returns true if the libvmaf this crate was built with has the extractors this model needs.
+    /// The floating point extractors are only compiled in with the `float` feature
+    pub fn is_supported(&self) -> bool {
+        cfg!(feature = "float") || !self.requires_float()
+    }
+
+    fn validate(&self) -> Result<(), ModelError> {
+        let features = self.feature_names.len();
+
+        if features == 0 {
+            return Err(Report::new(ModelError::Invalid(
+                "`feature_names` is empty".to_string(),
+            )));
+        }
+
+        // The first slope and intercept are for the score
+        for (key, values) in [("slopes", &self.slopes), ("intercepts", &self.intercepts)] {
+            if self.norm_type == NormType::LinearRescale && values.len() != features + 1 {
+                return Err(Report::new(ModelError::Invalid(format!(
+                    "`{key}` has {} values, expected {} for {features} features",
+                    values.len(),
+                    features + 1
+                ))));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ModelDescription, ModelType, NormType};
+    use crate::model::error::ModelError;
+
+    #[test]
+    fn describe() {
+        let description = ModelDescription::from_path("./examples/vmaf_v0.6.1.json").unwrap();
+
+        assert_eq!(description.model_type, ModelType::LibSvmNuSvr);
+        assert_eq!(description.feature_names.len(), 6);
+        assert!(description
+            .feature_names
+            .contains(&"VMAF_integer_feature_motion2_score".to_string()));
+        assert_eq!(
+            description.extractors["VMAF_integer_feature"].len(),
+            description.feature_names.len()
+        );
+        assert_eq!(description.norm_type, NormType::LinearRescale);
+        assert_eq!(description.score_clip, Some((0.0, 100.0)));
+
+        let transform = description.score_transform.as_ref().unwrap();
+        assert!(transform.out_gte_in && !transform.out_lte_in);
+        assert_eq!(transform.p0, Some(1.70674692));
+
+        assert!(!description.requires_float());
+        assert!(description.is_supported());
+    }
+
+    #[test]
+    fn invalid() {
+        let reason = |json: &str| match ModelDescription::from_json_str(json)
+            .unwrap_err()
+            .current_context()
+        {
+            ModelError::Invalid(reason) => reason.clone(),
+            e => panic!("Unexpected error {e}"),
+        };
+
+        assert!(reason("{}").contains("model_dict"));
+        assert!(
+            reason(r#"{"model_dict": {"feature_names": ["VMAF_feature_adm2_score"]}}"#)
+                .contains("model_type")
+        );
+        assert_eq!(
+            reason(r#"{"model_dict": {"model_type": "LIBSVMNUSVR", "feature_names": []}}"#),
+            "`feature_names` is empty"
+        );
+        assert_eq!(
+            reason(
+                r#"{"model_dict": {"model_type": "LIBSVMNUSVR", "feature_names": ["a"], "norm_type": "linear_rescale", "slopes": [1.0], "intercepts": [0.0, 0.0]}}"#
+            ),
+            "`slopes` has 1 values, expected 2 for 1 features"
+        );
+
+        let float = ModelDescription::from_json_str(
+            r#"{"model_dict": {"model_type": "LIBSVMNUSVR", "feature_names": ["VMAF_feature_adm2_score"]}}"#,
+        )
+        .unwrap();
+        assert!(float.requires_float());
+    }
+}
@@ -2,10 +2,28 @@
 
 use thiserror::Error;
 
+use super::builtin::BuiltinModel;
+
 #[derive(Error, Debug)]
 pub enum ModelError {
     #[error("Couldn't load model {0}")]
     Load(String),
     #[error("Couldn't load model from path {0}")]
     Path(Box<PathBuf>),
+    #[error("{0:?} isn't a built-in model. Expected one of {}", known_models())]
+    Unknown(String),
+    #[error("Invalid model: {0}")]
+    Invalid(String),
+    #[error("Couldn't write model to a temporary file")]
+    TempFile,
+    #[error("Couldn't overload options of feature extractor {0}")]
+    Overload(String),
+}
+
+fn known_models() -> String {
+    BuiltinModel::ALL
+        .iter()
+        .map(BuiltinModel::version)
+        .collect::<Vec<_>>()
+        .join(", ")
 }
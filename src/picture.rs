@@ -1,20 +1,24 @@
 use error_stack::{Report, Result, ResultExt};
-use ffmpeg_next::{format::Pixel, frame::Video as VideoFrame};
-use libc::{self, c_void, memcpy};
+use ffmpeg_next::frame::Video as VideoFrame;
+use libc::{self, c_void};
 pub use libvmaf_sys::VmafPixelFormat;
 use libvmaf_sys::{vmaf_picture_alloc, vmaf_picture_unref, VmafPicture};
 use ptrplus::{AsPtr, FromRaw, IntoRaw};
-use std::{ffi::c_uint, marker::PhantomData, mem};
+use std::{ffi::c_uint, marker::PhantomData, mem, slice};
 
 use crate::{error::FFIError, picture::error::PictureError};
 
+use self::format::PixelLayout;
+
 pub mod error;
+pub mod format;
 /// A safe wrapper around `*mut VmafPicture`
 ///
 /// Unless you're trying to use a library besides FFMPEG for decoding video,
 /// you shouldn't concern yourself with this struct
 pub struct Picture<State: Consumable = ValidRef> {
     vmaf_picture: Option<*mut VmafPicture>,
+    pts: Option<i64>,
     consumed: PhantomData<State>,
 }
 
@@ -46,6 +50,7 @@ impl Picture {
 
         Ok(Picture {
             vmaf_picture: Some(pic),
+            pts: None,
             consumed: PhantomData,
         })
     }
@@ -55,50 +60,115 @@ impl Picture {
     pub fn consume(self) -> Picture<Consumed> {
         Picture {
             vmaf_picture: None,
+            pts: self.pts,
             consumed: PhantomData,
         }
     }
+
+    /// Presentation timestamp of the frame this picture was made from, in units of its source's time base
+    pub fn pts(&self) -> Option<i64> {
+        self.pts
+    }
+
+    pub fn set_pts(&mut self, pts: Option<i64>) {
+        self.pts = pts;
+    }
+
+    pub fn pix_fmt(&self) -> VmafPixelFormat {
+        unsafe { (*self.as_ptr()).pix_fmt }
+    }
+
+    pub fn bits_per_channel(&self) -> u32 {
+        unsafe { (*self.as_ptr()).bpc }
+    }
+
+    /// Width of `plane` in values. Plane 0 is luma, planes 1 and 2 are chroma
+    pub fn plane_width(&self, plane: usize) -> usize {
+        unsafe { (*self.as_ptr()).w[plane] as usize }
+    }
+
+    /// Height of `plane` in rows. Plane 0 is luma, planes 1 and 2 are chroma
+    pub fn plane_height(&self, plane: usize) -> usize {
+        unsafe { (*self.as_ptr()).h[plane] as usize }
+    }
+
+    /// Size of a single value in bytes. Values wider than 8 bits take two bytes
+    pub fn bytes_per_value(&self) -> usize {
+        if self.bits_per_channel() > 8 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// The `y`th row of `plane`, without the padding at the end of each row
+    pub fn row(&self, plane: usize, y: usize) -> &[u8] {
+        assert!(y < self.plane_height(plane), "Row {y} is out of bounds");
+        let len = self.plane_width(plane) * self.bytes_per_value();
+
+        unsafe {
+            let pic = self.as_ptr();
+            let data = ((*pic).data[plane] as *const u8)
+                .offset((*pic).stride[plane] as isize * y as isize);
+            slice::from_raw_parts(data, len)
+        }
+    }
+
+    /// The `y`th row of `plane`, without the padding at the end of each row
+    pub fn row_mut(&mut self, plane: usize, y: usize) -> &mut [u8] {
+        assert!(y < self.plane_height(plane), "Row {y} is out of bounds");
+        let len = self.plane_width(plane) * self.bytes_per_value();
+
+        unsafe {
+            let pic = self.as_ptr();
+            let data =
+                ((*pic).data[plane] as *mut u8).offset((*pic).stride[plane] as isize * y as isize);
+            slice::from_raw_parts_mut(data, len)
+        }
+    }
+
+    /// Append every row of every plane to `buffer`, without padding
+    pub(crate) fn extend_with_planes(&self, buffer: &mut Vec<u8>) {
+        for plane in 0..3 {
+            for y in 0..self.plane_height(plane) {
+                buffer.extend_from_slice(self.row(plane, y));
+            }
+        }
+    }
+
+    /// Fill every plane from `data`, as written by [`Picture::extend_with_planes`]
+    pub(crate) fn fill_planes(&mut self, mut data: &[u8]) {
+        for plane in 0..3 {
+            for y in 0..self.plane_height(plane) {
+                let row = self.row_mut(plane, y);
+                let (src, rest) = data.split_at(row.len());
+                row.copy_from_slice(src);
+                data = rest;
+            }
+        }
+    }
 }
 
 impl TryFrom<VideoFrame> for Picture {
     type Error = Report<PictureError>;
 
     fn try_from(frame: VideoFrame) -> core::result::Result<Self, Self::Error> {
-        // Get pixel format
-        let format = match frame.format() {
-            Pixel::YUV420P | Pixel::YUV420P10LE | Pixel::YUV420P12LE | Pixel::YUV420P16LE => {
-                VmafPixelFormat::VMAF_PIX_FMT_YUV420P
-            }
-            Pixel::YUV422P | Pixel::YUV422P10LE | Pixel::YUV422P12LE | Pixel::YUV422P16LE => {
-                VmafPixelFormat::VMAF_PIX_FMT_YUV422P
-            }
-            Pixel::YUV444P | Pixel::YUV444P10LE | Pixel::YUV444P12LE | Pixel::YUV444P16LE => {
-                VmafPixelFormat::VMAF_PIX_FMT_YUV444P
-            }
-            _ => VmafPixelFormat::VMAF_PIX_FMT_UNKNOWN,
-        };
-
-        // Get bits per channel
-        let bits_per_channel: u32 = match frame.format() {
-            Pixel::YUV420P | Pixel::YUV422P | Pixel::YUV444P => 8,
-            Pixel::YUV420P10LE | Pixel::YUV422P10LE | Pixel::YUV444P10LE => 10,
-            Pixel::YUV420P12LE | Pixel::YUV422P12LE | Pixel::YUV444P12LE => 12,
-            Pixel::YUV420P16LE | Pixel::YUV422P16LE | Pixel::YUV444P16LE => 16,
-            _ => {
-                return Err(Report::new(PictureError::Decode)
-                    .attach_printable(format!("{:?}", frame.format())))
-            }
-        };
+        // Work out how this frame has to be normalized before libvmaf can read it
+        let layout = PixelLayout::try_from(frame.format())
+            .map_err(|reason| Report::new(PictureError::Format(frame.format(), reason)))?;
 
-        let picture = Picture::new(format, bits_per_channel, frame.width(), frame.height())?;
+        let mut picture = Picture::new(
+            layout.format,
+            layout.target_bits_per_channel(),
+            frame.width(),
+            frame.height(),
+        )?;
+        picture.set_pts(frame.pts());
 
         let src = unsafe { frame.as_ptr() };
         let dst = picture.as_ptr();
         // Fill pixel data
-        let bytes_per_value: usize = match bits_per_channel {
-            0..=8 => 1,
-            _ => 2,
-        };
+        let bytes_per_value = layout.bytes_per_value();
 
         let conversion_handler = |e| {
             Err(Report::new(e)
@@ -108,11 +178,15 @@ impl TryFrom<VideoFrame> for Picture {
 
         unsafe {
             for i in 0..3 {
-                let mut src_data: *const c_void = (*src).data[i] as *const c_void;
-                let mut dst_data = (*dst).data[i];
+                let mut src_data = (*src).data[i] as *const u8;
+                let mut dst_data = (*dst).data[i] as *mut u8;
+                let row_bytes = bytes_per_value * (*dst).w[i] as usize;
 
                 for _ in 0..(*dst).h[i] {
-                    memcpy(dst_data, src_data, bytes_per_value * (*dst).w[i] as usize);
+                    layout.convert_row(
+                        slice::from_raw_parts(src_data, row_bytes),
+                        slice::from_raw_parts_mut(dst_data, row_bytes),
+                    );
 
                     let linesize_src = match (*src).linesize[i].try_into() {
                         Ok(n) => n,
@@ -157,11 +231,15 @@ impl FromRaw<VmafPicture> for Picture {
     unsafe fn from_raw(raw: *mut VmafPicture) -> Self {
         Self {
             vmaf_picture: Some(raw),
+            pts: None,
             consumed: PhantomData,
         }
     }
 }
 
+// VmafPicture's buffer is reference counted with atomics, so a Picture may be released from any thread
+unsafe impl<T: Consumable> Send for Picture<T> {}
+
 impl<T: Consumable> Drop for Picture<T> {
     fn drop(&mut self) {
         // Allow FFI code to free its memory
@@ -188,4 +266,16 @@ mod test {
         let _pic = Picture::new(VmafPixelFormat::VMAF_PIX_FMT_YUV422P, 8, 1920, 1080)
             .expect("Recieved error code from constructor");
     }
+
+    #[test]
+    fn rows() {
+        let mut pic = Picture::new(VmafPixelFormat::VMAF_PIX_FMT_YUV420P, 10, 64, 32).unwrap();
+
+        assert_eq!(pic.plane_width(1), 32);
+        assert_eq!(pic.plane_height(1), 16);
+        assert_eq!(pic.row(0, 0).len(), 128);
+
+        pic.row_mut(2, 15).fill(0xAB);
+        assert!(pic.row(2, 15).iter().all(|byte| *byte == 0xAB));
+    }
 }
@@ -1,3 +1,4 @@
+use ffmpeg_next::format::Pixel;
 use thiserror::Error;
 
 /// An error context for Vmaf Pictures
@@ -9,4 +10,21 @@ pub enum PictureError {
     /// There was a problem decoding a picture
     #[error("Encountered a problem when trying to decode video")]
     Decode,
-}
\ No newline at end of file
+    /// A frame's pixel format can't be converted into one libvmaf accepts
+    #[error("Unsupported pixel format {0:?}: {1}")]
+    Format(Pixel, FormatError),
+}
+
+/// The reason a pixel format couldn't be converted into a [`PixelLayout`](super::format::PixelLayout)
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatError {
+    /// libvmaf only reads planar YUV. Packed, semi-planar, RGB and hardware formats should be converted by the scaler first
+    #[error("not a planar YUV format")]
+    NotPlanarYuv,
+    /// The format carries an alpha plane
+    #[error("formats with an alpha plane are not supported")]
+    Alpha,
+    /// libvmaf has no equivalent for this chroma subsampling (4:1:0, 4:1:1, 4:4:0)
+    #[error("chroma subsampling is not supported")]
+    Subsampling,
+}
@@ -0,0 +1,220 @@
+use ffmpeg_next::format::Pixel;
+use libvmaf_sys::VmafPixelFormat;
+
+use super::error::FormatError;
+
+/// Byte order of the samples in a plane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// Byte order of the machine we're running on. This is the byte order libvmaf expects high bit depth samples in
+    pub fn native() -> Endianness {
+        if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+}
+
+/// Describes the planes of an FFMPEG pixel format, and how they have to be normalized before libvmaf can read them
+///
+/// libvmaf only understands planar YUV in native byte order at a bit depth of 8, 10, 12 or 16.
+/// Big endian frames are byte swapped, and 9 and 14 bit frames are rescaled to the next bit depth up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelLayout {
+    pub format: VmafPixelFormat,
+    pub bits_per_channel: u32,
+    pub endianness: Endianness,
+}
+
+impl PixelLayout {
+    /// Bit depth of the `Picture` this layout is converted into
+    pub fn target_bits_per_channel(&self) -> u32 {
+        match self.bits_per_channel {
+            9 => 10,
+            14 => 16,
+            n => n,
+        }
+    }
+
+    /// Number of bytes used to store a single sample
+    pub fn bytes_per_value(&self) -> usize {
+        match self.bits_per_channel {
+            0..=8 => 1,
+            _ => 2,
+        }
+    }
+
+    /// Returns true if rows of this layout may be copied into a `Picture` verbatim
+    pub fn is_native(&self) -> bool {
+        self.bytes_per_value() == 1
+            || (self.endianness == Endianness::native()
+                && self.bits_per_channel == self.target_bits_per_channel())
+    }
+
+    /// Copy a row of samples from `src` into `dst`, byte swapping and rescaling as required.
+    /// Both slices should be `bytes_per_value() * width` long
+    pub fn convert_row(&self, src: &[u8], dst: &mut [u8]) {
+        if self.is_native() {
+            dst.copy_from_slice(src);
+            return;
+        }
+
+        let shift = self.target_bits_per_channel() - self.bits_per_channel;
+
+        for (src, dst) in src.chunks_exact(2).zip(dst.chunks_exact_mut(2)) {
+            let raw = [src[0], src[1]];
+            let value = match self.endianness {
+                Endianness::Little => u16::from_le_bytes(raw),
+                Endianness::Big => u16::from_be_bytes(raw),
+            };
+            dst.copy_from_slice(&(value << shift).to_ne_bytes());
+        }
+    }
+}
+
+impl TryFrom<Pixel> for PixelLayout {
+    type Error = FormatError;
+
+    fn try_from(pixel: Pixel) -> Result<Self, Self::Error> {
+        use Endianness::{Big, Little};
+        use VmafPixelFormat::{
+            VMAF_PIX_FMT_YUV420P as P420, VMAF_PIX_FMT_YUV422P as P422,
+            VMAF_PIX_FMT_YUV444P as P444,
+        };
+
+        let (format, bits_per_channel, endianness) = match pixel {
+            Pixel::YUV420P | Pixel::YUVJ420P => (P420, 8, Little),
+            Pixel::YUV420P9LE => (P420, 9, Little),
+            Pixel::YUV420P9BE => (P420, 9, Big),
+            Pixel::YUV420P10LE => (P420, 10, Little),
+            Pixel::YUV420P10BE => (P420, 10, Big),
+            Pixel::YUV420P12LE => (P420, 12, Little),
+            Pixel::YUV420P12BE => (P420, 12, Big),
+            Pixel::YUV420P14LE => (P420, 14, Little),
+            Pixel::YUV420P14BE => (P420, 14, Big),
+            Pixel::YUV420P16LE => (P420, 16, Little),
+            Pixel::YUV420P16BE => (P420, 16, Big),
+
+            Pixel::YUV422P | Pixel::YUVJ422P => (P422, 8, Little),
+            Pixel::YUV422P9LE => (P422, 9, Little),
+            Pixel::YUV422P9BE => (P422, 9, Big),
+            Pixel::YUV422P10LE => (P422, 10, Little),
+            Pixel::YUV422P10BE => (P422, 10, Big),
+            Pixel::YUV422P12LE => (P422, 12, Little),
+            Pixel::YUV422P12BE => (P422, 12, Big),
+            Pixel::YUV422P14LE => (P422, 14, Little),
+            Pixel::YUV422P14BE => (P422, 14, Big),
+            Pixel::YUV422P16LE => (P422, 16, Little),
+            Pixel::YUV422P16BE => (P422, 16, Big),
+
+            Pixel::YUV444P | Pixel::YUVJ444P => (P444, 8, Little),
+            Pixel::YUV444P9LE => (P444, 9, Little),
+            Pixel::YUV444P9BE => (P444, 9, Big),
+            Pixel::YUV444P10LE => (P444, 10, Little),
+            Pixel::YUV444P10BE => (P444, 10, Big),
+            Pixel::YUV444P12LE => (P444, 12, Little),
+            Pixel::YUV444P12BE => (P444, 12, Big),
+            Pixel::YUV444P14LE => (P444, 14, Little),
+            Pixel::YUV444P14BE => (P444, 14, Big),
+            Pixel::YUV444P16LE => (P444, 16, Little),
+            Pixel::YUV444P16BE => (P444, 16, Big),
+
+            Pixel::YUVA420P
+            | Pixel::YUVA422P
+            | Pixel::YUVA444P
+            | Pixel::YUVA420P9LE
+            | Pixel::YUVA420P9BE
+            | Pixel::YUVA422P9LE
+            | Pixel::YUVA422P9BE
+            | Pixel::YUVA444P9LE
+            | Pixel::YUVA444P9BE
+            | Pixel::YUVA420P10LE
+            | Pixel::YUVA420P10BE
+            | Pixel::YUVA422P10LE
+            | Pixel::YUVA422P10BE
+            | Pixel::YUVA444P10LE
+            | Pixel::YUVA444P10BE
+            | Pixel::YUVA422P12LE
+            | Pixel::YUVA422P12BE
+            | Pixel::YUVA444P12LE
+            | Pixel::YUVA444P12BE
+            | Pixel::YUVA420P16LE
+            | Pixel::YUVA420P16BE
+            | Pixel::YUVA422P16LE
+            | Pixel::YUVA422P16BE
+            | Pixel::YUVA444P16LE
+            | Pixel::YUVA444P16BE => return Err(FormatError::Alpha),
+
+            Pixel::YUV410P
+            | Pixel::YUV411P
+            | Pixel::YUVJ411P
+            | Pixel::YUV440P
+            | Pixel::YUVJ440P
+            | Pixel::YUV440P10LE
+            | Pixel::YUV440P10BE
+            | Pixel::YUV440P12LE
+            | Pixel::YUV440P12BE => return Err(FormatError::Subsampling),
+
+            _ => return Err(FormatError::NotPlanarYuv),
+        };
+
+        Ok(PixelLayout {
+            format,
+            bits_per_channel,
+            endianness,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ffmpeg_next::format::Pixel;
+    use libvmaf_sys::VmafPixelFormat;
+
+    use super::{Endianness, PixelLayout};
+    use crate::picture::error::FormatError;
+
+    #[test]
+    fn layouts() {
+        let layout = PixelLayout::try_from(Pixel::YUV420P10BE).unwrap();
+        assert_eq!(layout.format, VmafPixelFormat::VMAF_PIX_FMT_YUV420P);
+        assert_eq!(layout.bits_per_channel, 10);
+        assert_eq!(layout.endianness, Endianness::Big);
+
+        let layout = PixelLayout::try_from(Pixel::YUV444P14LE).unwrap();
+        assert_eq!(layout.target_bits_per_channel(), 16);
+
+        assert!(matches!(
+            PixelLayout::try_from(Pixel::NV12),
+            Err(FormatError::NotPlanarYuv)
+        ));
+        assert!(matches!(
+            PixelLayout::try_from(Pixel::YUVA420P),
+            Err(FormatError::Alpha)
+        ));
+        assert!(matches!(
+            PixelLayout::try_from(Pixel::YUVA444P12LE),
+            Err(FormatError::Alpha)
+        ));
+    }
+
+    #[test]
+    fn convert_row() {
+        let layout = PixelLayout::try_from(Pixel::YUV420P10BE).unwrap();
+        let mut dst = [0u8; 4];
+        layout.convert_row(&[0x03, 0xFF, 0x00, 0x01], &mut dst);
+        assert_eq!(dst[..2], 0x03FFu16.to_ne_bytes());
+        assert_eq!(dst[2..], 0x0001u16.to_ne_bytes());
+
+        let layout = PixelLayout::try_from(Pixel::YUV422P9LE).unwrap();
+        let mut dst = [0u8; 2];
+        layout.convert_row(&0x01FFu16.to_le_bytes(), &mut dst);
+        assert_eq!(dst, 0x03FEu16.to_ne_bytes());
+    }
+}
@@ -1,24 +1,33 @@
-use error_stack::{IntoReport, Result, ResultExt};
+use error_stack::{IntoReport, Report, Result, ResultExt};
 use ffmpeg_next::{
     codec::context::Context as Codec,
     codec::decoder::Video as VideoDecoder,
-    format::{context::Input, input, Pixel},
+    format::{context::Input, input, input_with_dictionary, Pixel},
     frame::Video as VideoFrame,
     media::Type,
     software::scaling,
     software::scaling::Context as Scaler,
     threading::Type as ThreadingType,
-    Error as AVError, Stream,
+    Dictionary, Error as AVError, Rational, Stream,
 };
 use std::path::Path;
 
 use self::{
+    crop::{Crop, ValidCrop},
     error::VideoError,
     resolution::{GetResolution, Resolution},
+    timebase::GetTimeBase,
 };
 
+pub mod cache;
+pub mod crop;
+pub mod cropdetect;
 pub mod error;
+#[cfg(test)]
+pub(crate) mod fixture;
 pub mod resolution;
+pub mod scenedetect;
+pub mod timebase;
 
 /// This struct represents a Video context. It contains the input file, decoder, and software scaler  
 /// This struct implements `Iterator<Item = VideoFrame>`, or, an iterator of frames
@@ -29,6 +38,9 @@ pub struct Video {
     scaler: Scaler,
     number_of_frames: i64,
     resolution: Resolution,
+    crop: Option<ValidCrop>,
+    time_base: Rational,
+    start_time: i64,
 }
 
 impl GetResolution for Video {
@@ -37,6 +49,16 @@ impl GetResolution for Video {
     }
 }
 
+impl GetTimeBase for Video {
+    fn get_time_base(&self) -> Option<Rational> {
+        (self.time_base.denominator() != 0).then_some(self.time_base)
+    }
+
+    fn get_start_time(&self) -> i64 {
+        self.start_time
+    }
+}
+
 impl Video {
     /// Construct a new Video context. Path should be a path to a video file. The video file may be of any file format, but the pixel format should be in YUV format.
     /// set w and h to your desired resolution and
@@ -51,6 +73,43 @@ impl Video {
             .into_report()
             .change_context(VideoError::Construct(path.as_ref().to_owned()))?;
 
+        Video::from_input(path, input, w, h)
+    }
+
+    /// Construct a new Video context from a file of raw, headerless YUV frames of `format`, such as those written by the upstream `vmaf` tool.
+    /// The file must have a `.yuv` extension so that FFMPEG recognises it. Frames are output at their original `width` and `height`
+    pub fn new_raw<P: AsRef<Path>>(
+        path: P,
+        format: Pixel,
+        width: u32,
+        height: u32,
+    ) -> Result<Video, VideoError> {
+        ffmpeg_next::init()
+            .into_report()
+            .change_context(VideoError::Construct(path.as_ref().to_owned()))?;
+
+        let pixel_format = format
+            .descriptor()
+            .ok_or_else(|| Report::new(VideoError::Format(format)))?
+            .name();
+
+        let mut options = Dictionary::new();
+        options.set("video_size", &format!("{width}x{height}"));
+        options.set("pixel_format", pixel_format);
+
+        let input = input_with_dictionary(&path, options)
+            .into_report()
+            .change_context(VideoError::Construct(path.as_ref().to_owned()))?;
+
+        Video::from_input(path, input, width, height)
+    }
+
+    fn from_input<P: AsRef<Path>>(
+        path: P,
+        input: Input,
+        w: u32,
+        h: u32,
+    ) -> Result<Video, VideoError> {
         // Get index of best video stream
         let input_stream: Stream = input
             .streams()
@@ -59,7 +118,17 @@ impl Video {
             .into_report()
             .change_context(VideoError::Construct(path.as_ref().to_owned()))?;
 
-        let number_of_frames = input_stream.frames();
+        // Containers without an index, such as raw YUV, don't say how many frames they have, so estimate from their duration
+        let number_of_frames = match input_stream.frames() {
+            0 => estimate_frames(&input_stream),
+            n => n,
+        };
+        let time_base = input_stream.time_base();
+        // Streams which don't say when they start are assumed to start at 0
+        let start_time = match input_stream.start_time() {
+            ffmpeg_next::ffi::AV_NOPTS_VALUE => 0,
+            start_time => start_time,
+        };
 
         let video_index = input_stream.index();
 
@@ -131,14 +200,57 @@ impl Video {
             scaler,
             number_of_frames,
             resolution,
+            crop: None,
+            time_base,
+            start_time,
         })
     }
 
     pub fn get_num_frames(&self) -> i64 {
         self.number_of_frames
     }
+
+    /// Crop every frame of this video to `crop` after it is scaled. `crop` is given in the coordinates of the scaled frame,
+    /// and `get_resolution()` will report the size of the cropped region from then on.
+    ///
+    /// Apply the same crop to both reference and distorted videos so that `Vmaf::get_vmaf_scores()` still sees matching resolutions
+    pub fn crop(mut self, crop: Crop) -> Result<Video, VideoError> {
+        let output = self.scaler.output();
+        let format = output.format;
+
+        let scaled = Resolution::new(output.width, output.height)
+            .change_context(VideoError::Resolution(self.resolution.clone()))?;
+
+        let crop = ValidCrop::new(crop, format, scaled)?;
+
+        self.resolution = crop.crop().resolution();
+        self.crop = Some(crop);
+
+        Ok(self)
+    }
 }
 
+/// Estimate the number of frames in `stream` from its duration and average frame rate, or 0 if either is unknown
+fn estimate_frames(stream: &Stream) -> i64 {
+    let duration = stream.duration();
+    let (time_base, frame_rate) = (stream.time_base(), stream.avg_frame_rate());
+
+    if duration <= 0 || time_base.denominator() == 0 || frame_rate.denominator() == 0 {
+        return 0;
+    }
+
+    let frames = i128::from(duration)
+        * i128::from(time_base.numerator())
+        * i128::from(frame_rate.numerator())
+        / (i128::from(time_base.denominator()) * i128::from(frame_rate.denominator()));
+
+    frames.try_into().unwrap_or(0)
+}
+
+// The demuxer, decoder and scaler contexts aren't tied to the thread which created them,
+// and are only ever used through `&mut self`
+unsafe impl Send for Video {}
+
 impl ExactSizeIterator for Video {}
 
 impl Iterator for Video {
@@ -169,8 +281,15 @@ impl Iterator for Video {
                 Ok(_) => {
                     let mut scaled_frame = VideoFrame::empty();
                     self.scaler.run(&frame, &mut scaled_frame).unwrap();
-                    self.number_of_frames = self.number_of_frames - 1;
-                    assert!(self.number_of_frames >= 0);
+                    // The scaler doesn't carry timestamps over
+                    scaled_frame.set_pts(frame.timestamp());
+                    // An estimated frame count may fall short
+                    self.number_of_frames = (self.number_of_frames - 1).max(0);
+
+                    if let Some(crop) = &self.crop {
+                        return Some(crop.apply(&scaled_frame));
+                    }
+
                     return Some(scaled_frame);
                 }
                 Err(_) => continue,
@@ -198,8 +317,11 @@ impl Iterator for Video {
 mod test {
     use crate::picture::Picture;
 
-    use super::Video;
-    use std::path::Path;
+    use super::{
+        crop::Crop, error::VideoError, resolution::GetResolution, timebase::GetTimeBase, Video,
+    };
+    use ffmpeg_next::format::Pixel;
+    use std::{io::Write, path::Path};
 
     #[test]
     fn iterate() {
@@ -213,6 +335,109 @@ mod test {
         }
     }
 
+    #[test]
+    fn crop() {
+        let path = Path::new("./video/Big Buck Bunny 720P.m4v");
+
+        let vid: Video = Video::new(&path, 1920, 1080)
+            .unwrap()
+            .crop(Crop::new(0, 140, 1920, 800))
+            .unwrap();
+
+        assert_eq!(vid.get_width(), 1920);
+        assert_eq!(vid.get_height(), 800);
+
+        for _frame in vid.take(10) {
+            assert_eq!(_frame.height(), 800);
+            let _picture: Picture = _frame.try_into().unwrap();
+        }
+
+        let _v = Video::new(&path, 1920, 1080)
+            .unwrap()
+            .crop(Crop::new(0, 0, 1920, 1200));
+
+        assert!(_v.is_err())
+    }
+
+    #[test]
+    fn timestamps() {
+        let path = Path::new("./video/Big Buck Bunny 720P.m4v");
+
+        let vid: Video = Video::new(&path, 640, 360).unwrap();
+        assert!(vid.get_time_base().is_some());
+
+        let pts: Vec<i64> = vid.take(10).map(|frame| frame.pts().unwrap()).collect();
+        assert!(pts.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    /// Three 64x48 yuv420p frames of increasing brightness
+    fn raw_frames() -> Vec<u8> {
+        (0..3u8)
+            .flat_map(|frame| {
+                let luma = std::iter::repeat(16 + frame * 64).take(64 * 48);
+                let chroma = std::iter::repeat(128).take(2 * 32 * 24);
+                luma.chain(chroma)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn raw() {
+        let mut file = tempfile::Builder::new().suffix(".yuv").tempfile().unwrap();
+        file.write_all(&raw_frames()).unwrap();
+
+        let vid = Video::new_raw(file.path(), Pixel::YUV420P, 64, 48).unwrap();
+        assert_eq!((vid.get_width(), vid.get_height()), (64, 48));
+        // Raw YUV has no frame count, so it's estimated from the duration
+        assert_eq!(vid.get_num_frames(), 3);
+
+        let frames: Vec<_> = vid.collect();
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|frame| frame.format() == Pixel::YUV420P));
+        assert_eq!(frames[2].data(0)[0], 16 + 2 * 64);
+    }
+
+    #[test]
+    fn crop_raw() {
+        let mut file = tempfile::Builder::new().suffix(".yuv").tempfile().unwrap();
+        file.write_all(&raw_frames()).unwrap();
+        let open = || Video::new_raw(file.path(), Pixel::YUV420P, 64, 48).unwrap();
+
+        // Chroma is subsampled, so odd edges can't be cropped to
+        assert!(matches!(
+            open()
+                .crop(Crop::new(1, 0, 32, 16))
+                .err()
+                .unwrap()
+                .current_context(),
+            VideoError::CropAlignment(..)
+        ));
+
+        let frames: Vec<_> = open().crop(Crop::new(2, 4, 32, 16)).unwrap().collect();
+        assert_eq!(frames.len(), 3);
+        assert!(frames
+            .iter()
+            .all(|frame| (frame.width(), frame.height()) == (32, 16)));
+        assert_eq!(frames[1].data(0)[0], 16 + 64);
+        assert_eq!(frames[1].data(1)[0], 128);
+    }
+
+    #[test]
+    fn estimate_frames() {
+        // Y4M headers don't say how many frames follow
+        let mut file = tempfile::Builder::new().suffix(".y4m").tempfile().unwrap();
+        file.write_all(b"YUV4MPEG2 W64 H48 F25:1 Ip A1:1 C420jpeg\n")
+            .unwrap();
+        for frame in raw_frames().chunks(64 * 48 * 3 / 2) {
+            file.write_all(b"FRAME\n").unwrap();
+            file.write_all(frame).unwrap();
+        }
+
+        let vid = Video::new(file.path(), 64, 48).unwrap();
+        assert_eq!(vid.len(), 3);
+        assert_eq!(vid.count(), 3);
+    }
+
     #[test]
     fn invalid_video() {
         let path = Path::new("./src/video.rs");
@@ -0,0 +1,333 @@
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use ffmpeg_next::Rational;
+use libvmaf_sys::VmafPixelFormat;
+use memmap2::Mmap;
+use std::{fs::File, io::Write};
+
+use crate::picture::{error::PictureError, Picture};
+
+use super::{
+    error::CacheError,
+    resolution::{GetResolution, Resolution},
+    timebase::GetTimeBase,
+};
+
+/// Where a [`ReferenceCache`] keeps its frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStorage {
+    /// On the heap
+    Memory,
+    /// In an anonymous temporary file, which is memory mapped once every frame has been written.
+    /// The file is removed by the operating system when the cache is dropped
+    TempFile,
+}
+
+enum Storage {
+    Memory(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl Storage {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Storage::Memory(bytes) => bytes,
+            Storage::Mapped(map) => map,
+        }
+    }
+}
+
+/// A reference video which has been decoded, scaled and converted once, so that it may be compared against many distorted videos
+///
+/// Frames are stored exactly as libvmaf reads them, so replaying the reference with [`ReferenceCache::frames`]
+/// is a copy rather than a decode
+/// ```ignore
+/// let reference = ReferenceCache::new(Video::new(&"reference.mkv", 1920, 1080)?, CacheStorage::TempFile, 8 << 30)?;
+///
+/// for trial in trials {
+///     let scores = Vmaf::default().get_vmaf_scores(reference.frames(), Video::new(&trial, 1920, 1080)?, model()?, None::<fn(VmafStatus)>)?;
+/// }
+/// ```
+pub struct ReferenceCache {
+    storage: Storage,
+    resolution: Resolution,
+    time_base: Option<Rational>,
+    start_time: i64,
+    pix_fmt: VmafPixelFormat,
+    bits_per_channel: u32,
+    frame_size: usize,
+    /// Presentation timestamp of every frame, in decode order
+    pts: Vec<Option<i64>>,
+}
+
+impl ReferenceCache {
+    /// Convert every frame of `frames` and store it in `storage`.
+    /// Fails with [`CacheError::Limit`] rather than store more than `limit` bytes
+    pub fn new<P, I>(
+        frames: I,
+        storage: CacheStorage,
+        limit: usize,
+    ) -> Result<ReferenceCache, CacheError>
+    where
+        P: TryInto<Picture, Error = Report<PictureError>>,
+        I: GetResolution + GetTimeBase + Iterator<Item = P>,
+    {
+        let resolution = frames.get_resolution().clone();
+        let time_base = frames.get_time_base();
+        let start_time = frames.get_start_time();
+
+        let mut file: Option<File> = match storage {
+            CacheStorage::Memory => None,
+            CacheStorage::TempFile => Some(
+                tempfile::tempfile()
+                    .into_report()
+                    .change_context(CacheError::TempFile)?,
+            ),
+        };
+
+        let mut memory: Vec<u8> = vec![];
+        let mut buffer: Vec<u8> = vec![];
+        let mut layout: Option<(VmafPixelFormat, u32)> = None;
+        let mut pts: Vec<Option<i64>> = vec![];
+
+        for (index, frame) in frames.enumerate() {
+            let picture: Picture = frame.try_into().change_context(CacheError::Frame(index))?;
+
+            let this_layout = (picture.pix_fmt(), picture.bits_per_channel());
+            let this_resolution = Resolution {
+                width: picture.plane_width(0),
+                height: picture.plane_height(0),
+            };
+
+            if *layout.get_or_insert(this_layout) != this_layout || this_resolution != resolution {
+                return Err(Report::new(CacheError::Mismatch(index)));
+            }
+
+            buffer.clear();
+            picture.extend_with_planes(&mut buffer);
+
+            if (pts.len() + 1) * buffer.len() > limit {
+                return Err(Report::new(CacheError::Limit(limit)));
+            }
+
+            match file.as_mut() {
+                Some(file) => file
+                    .write_all(&buffer)
+                    .into_report()
+                    .change_context(CacheError::TempFile)?,
+                None => memory.extend_from_slice(&buffer),
+            }
+
+            pts.push(picture.pts());
+        }
+
+        let storage = match file {
+            // An empty file can't be mapped
+            Some(file) if !pts.is_empty() => {
+                // Safety: The file is anonymous, so nothing else can modify it while it is mapped
+                let map = unsafe { Mmap::map(&file) }
+                    .into_report()
+                    .change_context(CacheError::TempFile)?;
+                Storage::Mapped(map)
+            }
+            _ => Storage::Memory(memory),
+        };
+
+        let (pix_fmt, bits_per_channel) =
+            layout.unwrap_or((VmafPixelFormat::VMAF_PIX_FMT_UNKNOWN, 0));
+
+        Ok(ReferenceCache {
+            storage,
+            resolution,
+            time_base,
+            start_time,
+            pix_fmt,
+            bits_per_channel,
+            frame_size: buffer.len(),
+            pts,
+        })
+    }
+
+    /// Replay the cached frames, in the order they were decoded
+    pub fn frames(&self) -> CachedFrames<'_> {
+        CachedFrames {
+            cache: self,
+            index: 0,
+        }
+    }
+
+    /// Number of cached frames
+    pub fn len(&self) -> usize {
+        self.pts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pts.is_empty()
+    }
+
+    /// Number of bytes taken by the cached frames
+    pub fn size(&self) -> usize {
+        self.frame_size * self.len()
+    }
+
+    fn frame(&self, index: usize) -> CachedFrame<'_> {
+        let start = index * self.frame_size;
+
+        CachedFrame {
+            data: &self.storage.bytes()[start..start + self.frame_size],
+            pix_fmt: self.pix_fmt,
+            bits_per_channel: self.bits_per_channel,
+            resolution: &self.resolution,
+            pts: self.pts[index],
+        }
+    }
+}
+
+impl GetResolution for ReferenceCache {
+    fn get_resolution(&self) -> &Resolution {
+        &self.resolution
+    }
+}
+
+/// A single frame of a [`ReferenceCache`]. Convert it into a [`Picture`] to give it to libvmaf
+#[derive(Debug, Clone, Copy)]
+pub struct CachedFrame<'a> {
+    data: &'a [u8],
+    pix_fmt: VmafPixelFormat,
+    bits_per_channel: u32,
+    resolution: &'a Resolution,
+    pts: Option<i64>,
+}
+
+impl TryFrom<CachedFrame<'_>> for Picture {
+    type Error = Report<PictureError>;
+
+    fn try_from(frame: CachedFrame<'_>) -> core::result::Result<Self, Self::Error> {
+        let width = frame
+            .resolution
+            .width
+            .try_into()
+            .into_report()
+            .change_context(PictureError::Construct)?;
+        let height = frame
+            .resolution
+            .height
+            .try_into()
+            .into_report()
+            .change_context(PictureError::Construct)?;
+
+        let mut picture = Picture::new(frame.pix_fmt, frame.bits_per_channel, width, height)?;
+        picture.set_pts(frame.pts);
+        picture.fill_planes(frame.data);
+
+        Ok(picture)
+    }
+}
+
+/// Iterator over the frames of a [`ReferenceCache`]. May be used anywhere a [`Video`](super::Video) is
+pub struct CachedFrames<'a> {
+    cache: &'a ReferenceCache,
+    index: usize,
+}
+
+impl<'a> Iterator for CachedFrames<'a> {
+    type Item = CachedFrame<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.cache.len() {
+            return None;
+        }
+
+        let frame = self.cache.frame(self.index);
+        self.index += 1;
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.cache.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for CachedFrames<'_> {}
+
+impl GetResolution for CachedFrames<'_> {
+    fn get_resolution(&self) -> &Resolution {
+        &self.cache.resolution
+    }
+}
+
+impl GetTimeBase for CachedFrames<'_> {
+    fn get_time_base(&self) -> Option<Rational> {
+        self.cache.time_base
+    }
+
+    fn get_start_time(&self) -> i64 {
+        self.cache.start_time
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CacheStorage, ReferenceCache};
+    use crate::{
+        model::{config::ModelConfig, Model},
+        video::{error::CacheError, fixture, timebase::GetTimeBase},
+        vmaf::{status::VmafStatus, Vmaf},
+    };
+
+    #[test]
+    fn replay() {
+        let model = || Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+
+        let reference = fixture::raw_video(5, 0);
+        let distorted = fixture::raw_video(5, 8);
+
+        let decoded = Vmaf::default()
+            .get_vmaf_scores(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model(),
+                None::<fn(VmafStatus)>,
+            )
+            .unwrap();
+
+        for storage in [CacheStorage::Memory, CacheStorage::TempFile] {
+            let cache =
+                ReferenceCache::new(fixture::open(&reference), storage, usize::MAX).unwrap();
+            assert_eq!(cache.len(), 5);
+            assert_eq!(
+                cache.frames().get_time_base(),
+                fixture::open(&reference).get_time_base()
+            );
+
+            let replayed = Vmaf::default()
+                .get_vmaf_scores(
+                    cache.frames(),
+                    fixture::open(&distorted),
+                    model(),
+                    None::<fn(VmafStatus)>,
+                )
+                .unwrap();
+
+            assert_eq!(replayed, decoded);
+        }
+    }
+
+    #[test]
+    fn limit() {
+        let reference = fixture::raw_video(5, 0);
+
+        // Room for one frame, but not two
+        let frame = (fixture::WIDTH * fixture::HEIGHT * 3 / 2) as usize;
+        let result = ReferenceCache::new(
+            fixture::open(&reference),
+            CacheStorage::Memory,
+            frame * 3 / 2,
+        );
+
+        assert!(matches!(
+            result.err().as_ref().map(|report| report.current_context()),
+            Some(CacheError::Limit(_))
+        ));
+    }
+}
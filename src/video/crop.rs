@@ -0,0 +1,167 @@
+use error_stack::{IntoReport, Result, ResultExt};
+use ffmpeg_next::{format::Pixel, frame::Video as VideoFrame};
+use std::fmt::Display;
+
+use crate::picture::format::PixelLayout;
+
+use super::{error::VideoError, resolution::Resolution};
+
+/// A rectangular region of a frame, in the coordinates of the scaled output of a [`Video`](super::Video)
+///
+/// Useful for removing letterboxing, or for scoring a picture-in-picture window on its own
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Crop {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Crop {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Crop {
+        Crop {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Resolution of frames after this crop is applied
+    pub fn resolution(&self) -> Resolution {
+        Resolution {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Returns true if this rectangle is non-empty and lies entirely within a frame of the given resolution
+    pub fn fits(&self, resolution: &Resolution) -> bool {
+        self.width > 0
+            && self.height > 0
+            && self.x + self.width <= resolution.width
+            && self.y + self.height <= resolution.height
+    }
+
+    /// Returns true if every edge of this rectangle falls on a chroma sample.
+    /// `log2_chroma_w` and `log2_chroma_h` are the chroma subsampling shifts of the pixel format, as reported by FFMPEG
+    pub fn is_aligned(&self, log2_chroma_w: u8, log2_chroma_h: u8) -> bool {
+        let mask_w = (1 << log2_chroma_w) - 1;
+        let mask_h = (1 << log2_chroma_h) - 1;
+
+        (self.x | self.width) & mask_w == 0 && (self.y | self.height) & mask_h == 0
+    }
+}
+
+impl Display for Crop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}+{}+{}", self.width, self.height, self.x, self.y)
+    }
+}
+
+/// A [`Crop`] which has been checked against the scaled frames of a video, so that applying it can't fail
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ValidCrop {
+    crop: Crop,
+    bytes_per_value: usize,
+    log2_chroma_w: u8,
+    log2_chroma_h: u8,
+}
+
+impl ValidCrop {
+    /// Check that `crop` fits within frames of `format` at `resolution`, and that its edges fall on chroma samples
+    pub(crate) fn new(
+        crop: Crop,
+        format: Pixel,
+        resolution: Resolution,
+    ) -> Result<ValidCrop, VideoError> {
+        if !crop.fits(&resolution) {
+            return Err(VideoError::Crop(crop, resolution).into());
+        }
+
+        // Cropping has to know how many bytes each sample takes up
+        let layout = PixelLayout::try_from(format)
+            .into_report()
+            .change_context(VideoError::Format(format))?;
+
+        let descriptor = format.descriptor().ok_or(VideoError::Format(format))?;
+        let (log2_chroma_w, log2_chroma_h) =
+            (descriptor.log2_chroma_w(), descriptor.log2_chroma_h());
+
+        if !crop.is_aligned(log2_chroma_w, log2_chroma_h) {
+            return Err(VideoError::CropAlignment(crop, format).into());
+        }
+
+        Ok(ValidCrop {
+            crop,
+            bytes_per_value: layout.bytes_per_value(),
+            log2_chroma_w,
+            log2_chroma_h,
+        })
+    }
+
+    pub(crate) fn crop(&self) -> Crop {
+        self.crop
+    }
+
+    /// Copy the cropped region out of `frame` into a new frame. `frame` has to have the format and resolution this crop was checked against
+    pub(crate) fn apply(&self, frame: &VideoFrame) -> VideoFrame {
+        let crop = &self.crop;
+        let mut cropped = VideoFrame::new(frame.format(), crop.width as u32, crop.height as u32);
+
+        for plane in 0..frame.planes() {
+            let (shift_w, shift_h) = match plane {
+                0 => (0, 0),
+                _ => (self.log2_chroma_w, self.log2_chroma_h),
+            };
+
+            let x = (crop.x >> shift_w) * self.bytes_per_value;
+            let y = crop.y >> shift_h;
+            let row_bytes = (crop.width >> shift_w) * self.bytes_per_value;
+            let rows = crop.height >> shift_h;
+
+            let src_stride = frame.stride(plane);
+            let dst_stride = cropped.stride(plane);
+            let src = frame.data(plane);
+            let dst = cropped.data_mut(plane);
+
+            for row in 0..rows {
+                let src_start = (y + row) * src_stride + x;
+                let dst_start = row * dst_stride;
+                dst[dst_start..dst_start + row_bytes]
+                    .copy_from_slice(&src[src_start..src_start + row_bytes]);
+            }
+        }
+
+        cropped.set_pts(frame.pts());
+
+        cropped
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Crop;
+    use crate::video::resolution::Resolution;
+
+    #[test]
+    fn fits() {
+        let resolution = Resolution {
+            width: 1920,
+            height: 1080,
+        };
+
+        assert!(Crop::new(0, 140, 1920, 800).fits(&resolution));
+        assert!(!Crop::new(0, 140, 1920, 1000).fits(&resolution));
+        assert!(!Crop::new(0, 0, 0, 1080).fits(&resolution));
+    }
+
+    #[test]
+    fn aligned() {
+        // 4:2:0
+        assert!(Crop::new(0, 140, 1920, 800).is_aligned(1, 1));
+        assert!(!Crop::new(1, 140, 1920, 800).is_aligned(1, 1));
+        // 4:2:2 only subsamples horizontally
+        assert!(Crop::new(0, 141, 1920, 801).is_aligned(1, 0));
+    }
+}
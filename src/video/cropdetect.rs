@@ -0,0 +1,205 @@
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use ffmpeg_next::frame::Video as VideoFrame;
+
+use crate::picture::format::{Endianness, PixelLayout};
+
+use super::{
+    crop::Crop,
+    error::VideoError,
+    resolution::{GetResolution, Resolution},
+};
+
+/// Detects constant black borders (letterboxing and pillarboxing) in a video, much like FFMPEG's `cropdetect` filter
+///
+/// Black bars are trivially identical between reference and distorted, so leaving them in inflates VMAF scores.
+/// The detected [`Crop`] is in the coordinates of the scaled frames, so it can be applied to both inputs with `Video::crop()`
+/// ```ignore
+/// let crop = CropDetect::default().detect(Video::new(&reference_path, 1920, 1080)?)?;
+/// let reference = Video::new(&reference_path, 1920, 1080)?.crop(crop)?;
+/// let distorted = Video::new(&distorted_path, 1920, 1080)?.crop(crop)?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CropDetect {
+    /// Number of frames to sample, spread evenly across the video
+    pub samples: usize,
+    /// A row or column is considered black if its average luma is at or below this value.
+    /// Given on an 8 bit scale, and scaled up for higher bit depths
+    pub limit: u16,
+}
+
+/// The area of a frame which contains picture content. `right` and `bottom` are exclusive
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Bounds {
+    left: usize,
+    top: usize,
+    right: usize,
+    bottom: usize,
+}
+
+impl Bounds {
+    fn union(self, other: Bounds) -> Bounds {
+        Bounds {
+            left: self.left.min(other.left),
+            top: self.top.min(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
+}
+
+impl CropDetect {
+    pub fn new(samples: usize, limit: u16) -> CropDetect {
+        CropDetect { samples, limit }
+    }
+
+    /// Sample frames from `frames` and return the smallest rectangle containing picture content in every sampled frame.
+    ///
+    /// Frames which are black in their entirety, such as fades, are ignored. If every sampled frame is black,
+    /// the full frame is returned.
+    /// Note that frames in between samples still have to be decoded
+    pub fn detect<I>(&self, frames: I) -> Result<Crop, VideoError>
+    where
+        I: GetResolution + ExactSizeIterator<Item = VideoFrame>,
+    {
+        let resolution = frames.get_resolution().clone();
+        let samples = self.samples.max(1);
+        let step = (frames.len() / samples).max(1);
+
+        let mut bounds: Option<Bounds> = None;
+        let mut alignment = (0, 0);
+
+        for frame in frames.step_by(step).take(samples) {
+            let format = frame.format();
+            let descriptor = format
+                .descriptor()
+                .ok_or_else(|| Report::new(VideoError::Format(format)))?;
+            alignment = (descriptor.log2_chroma_w(), descriptor.log2_chroma_h());
+
+            if let Some(frame_bounds) = self.frame_bounds(&frame)? {
+                bounds = Some(match bounds {
+                    Some(bounds) => bounds.union(frame_bounds),
+                    None => frame_bounds,
+                });
+            }
+        }
+
+        Ok(match bounds {
+            Some(bounds) => align(bounds, alignment.0, alignment.1),
+            None => Crop::new(0, 0, resolution.width, resolution.height),
+        })
+    }
+
+    fn frame_bounds(&self, frame: &VideoFrame) -> Result<Option<Bounds>, VideoError> {
+        let layout = PixelLayout::try_from(frame.format())
+            .into_report()
+            .change_context(VideoError::Format(frame.format()))?;
+
+        let resolution = Resolution {
+            width: frame.width() as usize,
+            height: frame.height() as usize,
+        };
+
+        let limit = u32::from(self.limit) << (layout.bits_per_channel - 8);
+        let bytes_per_value = layout.bytes_per_value();
+        let stride = frame.stride(0);
+        let data = frame.data(0);
+
+        let luma = |x: usize, y: usize| -> u32 {
+            let offset = y * stride + x * bytes_per_value;
+            match (bytes_per_value, layout.endianness) {
+                (1, _) => u32::from(data[offset]),
+                (_, Endianness::Little) => {
+                    u32::from(u16::from_le_bytes([data[offset], data[offset + 1]]))
+                }
+                (_, Endianness::Big) => {
+                    u32::from(u16::from_be_bytes([data[offset], data[offset + 1]]))
+                }
+            }
+        };
+
+        Ok(content_bounds(&resolution, limit, luma))
+    }
+}
+
+impl Default for CropDetect {
+    fn default() -> Self {
+        CropDetect::new(10, 24)
+    }
+}
+
+/// Find the rows and columns at the edges of a frame whose average luma is above `limit`
+fn content_bounds(
+    resolution: &Resolution,
+    limit: u32,
+    luma: impl Fn(usize, usize) -> u32,
+) -> Option<Bounds> {
+    let (width, height) = (resolution.width, resolution.height);
+
+    let is_content_row =
+        |y: usize| (0..width).map(|x| luma(x, y) as u64).sum::<u64>() > limit as u64 * width as u64;
+    let is_content_column = |x: usize| {
+        (0..height).map(|y| luma(x, y) as u64).sum::<u64>() > limit as u64 * height as u64
+    };
+
+    let top = (0..height).find(|&y| is_content_row(y))?;
+    let bottom = (0..height).rev().find(|&y| is_content_row(y))? + 1;
+    let left = (0..width).find(|&x| is_content_column(x))?;
+    let right = (0..width).rev().find(|&x| is_content_column(x))? + 1;
+
+    Some(Bounds {
+        left,
+        top,
+        right,
+        bottom,
+    })
+}
+
+/// Shrink `bounds` so that every edge falls on a chroma sample
+fn align(bounds: Bounds, log2_chroma_w: u8, log2_chroma_h: u8) -> Crop {
+    let round_up = |n: usize, shift: u8| ((n + (1 << shift) - 1) >> shift) << shift;
+    let round_down = |n: usize, shift: u8| (n >> shift) << shift;
+
+    let left = round_up(bounds.left, log2_chroma_w);
+    let top = round_up(bounds.top, log2_chroma_h);
+    let right = round_down(bounds.right, log2_chroma_w).max(left);
+    let bottom = round_down(bounds.bottom, log2_chroma_h).max(top);
+
+    Crop::new(left, top, right - left, bottom - top)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{align, content_bounds, Bounds};
+    use crate::video::{crop::Crop, resolution::Resolution};
+
+    #[test]
+    fn letterbox() {
+        let resolution = Resolution {
+            width: 16,
+            height: 12,
+        };
+
+        // Black bars above and below rows 3..9, with a dark but not black column at the left edge
+        let luma = |x: usize, y: usize| match (x, y) {
+            (_, 0..=2) | (_, 9..) => 16,
+            (0, _) => 40,
+            _ => 128,
+        };
+
+        let bounds = content_bounds(&resolution, 24, luma).unwrap();
+        assert_eq!(
+            bounds,
+            Bounds {
+                left: 0,
+                top: 3,
+                right: 16,
+                bottom: 9
+            }
+        );
+
+        // 4:2:0 chroma pushes the top edge down to the next even row
+        assert_eq!(align(bounds, 1, 1), Crop::new(0, 4, 16, 4));
+
+        assert!(content_bounds(&resolution, 24, |_, _| 0).is_none());
+    }
+}
@@ -2,7 +2,7 @@ use ffmpeg_next::format::Pixel;
 use std::path::PathBuf;
 use thiserror::Error;
 
-use super::resolution::Resolution;
+use super::{crop::Crop, resolution::Resolution};
 
 #[derive(Error, Debug)]
 pub enum VideoError {
@@ -12,4 +12,20 @@ pub enum VideoError {
     Resolution(Resolution),
     #[error("Cannot create a scaler given Pixel format {0:?}")]
     Format(Pixel),
+    #[error("Crop {0} doesn't fit within {1}")]
+    Crop(Crop, Resolution),
+    #[error("Crop {0} isn't aligned to the chroma planes of {1:?}")]
+    CropAlignment(Crop, Pixel),
+}
+
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("Couldn't convert reference frame #{0}")]
+    Frame(usize),
+    #[error("Reference frame #{0} doesn't have the same format or size as the first frame")]
+    Mismatch(usize),
+    #[error("Caching the reference would take more than {0} bytes")]
+    Limit(usize),
+    #[error("Couldn't write the cache to a temporary file")]
+    TempFile,
 }
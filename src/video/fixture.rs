@@ -0,0 +1,36 @@
+use std::io::Write;
+
+use ffmpeg_next::format::Pixel;
+use tempfile::NamedTempFile;
+
+use super::Video;
+
+pub(crate) const WIDTH: u32 = 176;
+pub(crate) const HEIGHT: u32 = 144;
+
+/// `frames` raw 176x144 yuv420p frames of a gradient which moves every frame.
+/// `noise` adds a fixed pattern of up to that much to every luma sample, so that a noisy copy scores below a clean one
+pub(crate) fn raw_video(frames: u8, noise: u8) -> NamedTempFile {
+    let mut file = tempfile::Builder::new().suffix(".yuv").tempfile().unwrap();
+
+    for frame in 0..frames {
+        let luma = (0..HEIGHT).flat_map(|y| {
+            (0..WIDTH).map(move |x| {
+                let gradient = (x + 2 * y + 4 * u32::from(frame)) % 160;
+                let noise = (x * 7 + y * 13) % (u32::from(noise) + 1);
+                (16 + gradient + noise) as u8
+            })
+        });
+        let chroma = std::iter::repeat(128).take((WIDTH * HEIGHT / 2) as usize);
+
+        file.write_all(&luma.chain(chroma).collect::<Vec<u8>>())
+            .unwrap();
+    }
+
+    file
+}
+
+/// Open a video written by [`raw_video`]
+pub(crate) fn open(file: &NamedTempFile) -> Video {
+    Video::new_raw(file.path(), Pixel::YUV420P, WIDTH, HEIGHT).unwrap()
+}
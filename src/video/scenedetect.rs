@@ -0,0 +1,132 @@
+use error_stack::{IntoReport, Result, ResultExt};
+use ffmpeg_next::frame::Video as VideoFrame;
+
+use crate::picture::format::{Endianness, PixelLayout};
+
+use super::error::VideoError;
+
+/// Number of bins in a luma histogram. Samples of every bit depth are reduced to 8 bits
+const BINS: usize = 256;
+
+/// Detects hard cuts between shots by comparing the luma histograms of consecutive frames
+///
+/// This is a simple detector meant for when no shot list is at hand. It finds hard cuts well, but misses
+/// most fades and dissolves. The cuts it returns can be turned into segments with `Segment::from_cuts()`
+/// ```ignore
+/// let cuts = SceneDetect::default().detect(Video::new(&reference_path, 1920, 1080)?)?;
+/// let segments = Segment::from_cuts(&cuts, frames);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SceneDetect {
+    /// A cut is detected when the histograms of consecutive frames differ by more than this fraction of their samples,
+    /// between 0 and 1
+    pub threshold: f64,
+    /// Minimum number of frames between cuts. Flashes and fast motion otherwise show up as several cuts in a row
+    pub min_length: u32,
+}
+
+impl SceneDetect {
+    pub fn new(threshold: f64, min_length: u32) -> SceneDetect {
+        SceneDetect {
+            threshold,
+            min_length,
+        }
+    }
+
+    /// Returns the index of the first frame of every shot after the first, in ascending order.
+    /// Every frame of `frames` is read, so pass in a fresh `Video` and not the one to be scored
+    pub fn detect<I>(&self, frames: I) -> Result<Vec<u32>, VideoError>
+    where
+        I: Iterator<Item = VideoFrame>,
+    {
+        let mut cuts = vec![];
+        let mut previous: Option<Vec<u64>> = None;
+        let mut last_cut = 0;
+
+        for (index, frame) in (0u32..).zip(frames) {
+            let current = histogram(&frame)?;
+
+            if let Some(previous) = &previous {
+                if index - last_cut >= self.min_length
+                    && difference(previous, &current) > self.threshold
+                {
+                    cuts.push(index);
+                    last_cut = index;
+                }
+            }
+
+            previous = Some(current);
+        }
+
+        Ok(cuts)
+    }
+}
+
+impl Default for SceneDetect {
+    fn default() -> Self {
+        SceneDetect::new(0.4, 12)
+    }
+}
+
+/// Histogram of the luma plane of `frame`
+fn histogram(frame: &VideoFrame) -> Result<Vec<u64>, VideoError> {
+    let layout = PixelLayout::try_from(frame.format())
+        .into_report()
+        .change_context(VideoError::Format(frame.format()))?;
+
+    let shift = layout.bits_per_channel - 8;
+    let bytes_per_value = layout.bytes_per_value();
+    let row_bytes = frame.width() as usize * bytes_per_value;
+    let stride = frame.stride(0);
+    let data = frame.data(0);
+
+    let mut histogram = vec![0u64; BINS];
+
+    for row in data.chunks(stride).take(frame.height() as usize) {
+        for value in row[..row_bytes].chunks_exact(bytes_per_value) {
+            let value = match (value, layout.endianness) {
+                ([value], _) => u16::from(*value),
+                ([a, b], Endianness::Little) => u16::from_le_bytes([*a, *b]),
+                (_, _) => u16::from_be_bytes([value[0], value[1]]),
+            };
+
+            histogram[usize::from(value >> shift).min(BINS - 1)] += 1;
+        }
+    }
+
+    Ok(histogram)
+}
+
+/// Fraction of samples which would have to change bins to turn histogram `a` into `b`
+fn difference(a: &[u64], b: &[u64]) -> f64 {
+    let total: u64 = a.iter().sum();
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    let moved: u64 = a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).sum();
+    moved as f64 / (2 * total) as f64
+}
+
+#[cfg(test)]
+mod test {
+    use super::{difference, BINS};
+
+    #[test]
+    fn difference_of_histograms() {
+        let mut dark = vec![0u64; BINS];
+        dark[16] = 100;
+
+        let mut bright = vec![0u64; BINS];
+        bright[235] = 100;
+
+        let mut mixed = vec![0u64; BINS];
+        mixed[16] = 75;
+        mixed[235] = 25;
+
+        assert_eq!(difference(&dark, &dark), 0.0);
+        assert_eq!(difference(&dark, &bright), 1.0);
+        assert_eq!(difference(&dark, &mixed), 0.25);
+    }
+}
@@ -0,0 +1,55 @@
+use ffmpeg_next::Rational;
+use std::time::Duration;
+
+/// Implemented by sources which know when their frames are presented, such as [`Video`](super::Video).
+/// Passing a reference to `Vmaf::with_time_base()` makes scores carry the presentation timestamp of their frame
+///
+/// A source which has no notion of time, such as raw frames generated in memory, returns `None`
+pub trait GetTimeBase {
+    /// The unit presentation timestamps of this source are given in, in seconds
+    fn get_time_base(&self) -> Option<Rational>;
+
+    /// Presentation timestamp of the start of this source, in units of its time base
+    fn get_start_time(&self) -> i64 {
+        0
+    }
+}
+
+/// Convert `pts`, given in units of `time_base`, to a duration.
+/// Subtract the start time of the stream from `pts` first to get the duration since the start of the stream.
+/// Returns `None` for negative timestamps and invalid time bases
+pub fn to_duration(pts: i64, time_base: Rational) -> Option<Duration> {
+    let (numerator, denominator) = (
+        i128::from(time_base.numerator()),
+        i128::from(time_base.denominator()),
+    );
+
+    if pts < 0 || numerator <= 0 || denominator <= 0 {
+        return None;
+    }
+
+    let nanos = i128::from(pts) * numerator * 1_000_000_000 / denominator;
+    Some(Duration::from_nanos(nanos.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod test {
+    use ffmpeg_next::Rational;
+    use std::time::Duration;
+
+    use super::to_duration;
+
+    #[test]
+    fn duration() {
+        assert_eq!(
+            to_duration(90_000, Rational::new(1, 90_000)),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            to_duration(3, Rational::new(1001, 24_000)),
+            Some(Duration::from_nanos(125_125_000))
+        );
+        assert_eq!(to_duration(-1, Rational::new(1, 1000)), None);
+        assert_eq!(to_duration(1, Rational::new(1, 0)), None);
+    }
+}
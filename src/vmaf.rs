@@ -1,26 +1,69 @@
+use self::cancel::CancelToken;
+use self::config::VmafConfig;
 use self::error::VmafError;
+use self::extractor::FeatureExtractor;
+use self::features::FeatureCache;
+use self::output::OutputFormat;
+use self::score::FrameScore;
 use self::status::VmafStatus;
 use crate::picture::ValidRef;
-use crate::video::resolution::GetResolution;
+use crate::video::{
+    resolution::GetResolution,
+    timebase::{to_duration, GetTimeBase},
+};
 use crate::{error::FFIError, picture::error::PictureError};
 use crate::{model::Model, picture::Picture};
-use error_stack::{bail, Report, Result, ResultExt};
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use ffmpeg_next::Rational;
 use libvmaf_sys::{vmaf_close, vmaf_init, VmafConfiguration, VmafContext};
 /// Re-export of Vmaf Log levels from `libvmaf-sys`
 pub use libvmaf_sys::{VmafLogLevel, VmafModel};
+use std::ffi::CString;
 use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
+pub mod batch;
+pub mod cancel;
+pub mod config;
 pub mod error;
+pub mod extractor;
+pub mod features;
 mod ffi;
+pub mod gate;
+#[cfg(feature = "tracing")]
+pub mod log;
+pub mod output;
+pub mod pooling;
+pub mod score;
+pub mod segment;
+pub mod stats;
 pub mod status;
+#[cfg(feature = "async")]
+pub mod stream;
 
 /// Safe wrapper around `*mut VmafContext`
 ///
 /// This is the main struct you should be concerned with
 /// if you want to calculate Vmaf scores
-pub struct Vmaf(*mut VmafContext);
+pub struct Vmaf {
+    ctx: *mut VmafContext,
+    config: VmafConfiguration,
+    cancel: Option<CancelToken>,
+    output: Option<(PathBuf, OutputFormat)>,
+    feature_cache: Option<FeatureCache>,
+    extractors: Vec<Box<dyn FeatureExtractor + Send>>,
+    /// Time base and start time of the reference, to convert presentation timestamps with
+    time_base: Option<(Rational, i64)>,
+}
 
 impl Vmaf {
+    /// Construct a new Vmaf context.
+    /// `cpumask` is a raw bitmask of the SIMD instruction sets libvmaf should not use.
+    /// [`VmafConfig`](config::VmafConfig) is a validated builder for these options
+    ///
+    /// If `n_subsample` is greater than 1, libvmaf only extracts features from every `n_subsample`th frame pair.
+    /// This gives a cheap estimate on long content, and `get_vmaf_scores()` will only return scores for those frames
     pub fn new(
         log_level: VmafLogLevel,
         n_threads: u32,
@@ -38,7 +81,15 @@ impl Vmaf {
 
         assert!(ctx.is_null());
 
-        let mut vmaf: Vmaf = Vmaf(ctx);
+        let mut vmaf: Vmaf = Vmaf {
+            ctx,
+            config,
+            cancel: None,
+            output: None,
+            feature_cache: None,
+            extractors: vec![],
+            time_base: None,
+        };
         // Let vmaf do its thing with our pointer
         let err = unsafe { vmaf_init(&mut *vmaf, config) };
 
@@ -48,30 +99,174 @@ impl Vmaf {
         // Return an error if vmaf_init returned an error code
         FFIError::check_err(err).change_context(VmafError::Construct)?;
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            log_level = ?log_level,
+            n_threads,
+            n_subsample,
+            cpumask,
+            "Constructed Vmaf context"
+        );
+
         Ok(vmaf)
     }
 
+    /// Stop `get_vmaf_scores()` early once `token` is cancelled.
+    /// See [`CancelToken`] for details
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Vmaf {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Have every score carry the presentation timestamp of its reference frame as a duration since the start of `reference`,
+    /// as well as the raw `pts`. Pass the reference before handing it to `get_vmaf_scores()`.
+    /// Without a time base, or if `reference` has none, scores only carry their raw `pts`
+    pub fn with_time_base(mut self, reference: &impl GetTimeBase) -> Vmaf {
+        self.time_base = reference
+            .get_time_base()
+            .map(|time_base| (time_base, reference.get_start_time()));
+        self
+    }
+
+    /// Have `get_vmaf_scores()` write libvmaf's report of every per-frame metric and pooled score to `path` once scoring finishes.
+    /// This is the same report the upstream `vmaf` tool writes, and includes any features added with [`Vmaf::use_feature`]
+    pub fn with_output(mut self, path: impl AsRef<Path>, format: OutputFormat) -> Vmaf {
+        self.output = Some((path.as_ref().to_path_buf(), format));
+        self
+    }
+
+    /// Extract the feature `name` on top of the features the model uses, such as `psnr`, `float_ssim` or `cambi`.
+    /// These features don't change the VMAF score, but are reported in the file written by [`Vmaf::with_output`]
+    pub fn use_feature(&mut self, name: &str) -> Result<(), VmafError> {
+        let error = || VmafError::Feature(Some(name.to_string()));
+
+        let name = CString::new(name)
+            .into_report()
+            .change_context_lazy(error)?;
+
+        self.use_feature_by_name(&name).change_context_lazy(error)
+    }
+
+    /// Store `value` as the score of the feature `name` for the frame at `index`, as if an extractor had computed it.
+    ///
+    /// Features named the way a model expects them, such as `VMAF_integer_feature_adm2_score`, are fused by that model
+    /// in [`Vmaf::get_imported_scores`]. Any other name is just reported in the file written by [`Vmaf::with_output`].
+    /// A frame can only have one score per feature
+    pub fn import_feature_score(
+        &mut self,
+        name: &str,
+        index: u32,
+        value: f64,
+    ) -> Result<(), VmafError> {
+        let error = || VmafError::Import {
+            name: name.to_string(),
+            index,
+        };
+
+        let name = CString::new(name)
+            .into_report()
+            .change_context_lazy(error)?;
+
+        self.import_feature_score_by_name(&name, index, value)
+            .change_context_lazy(error)
+    }
+
+    /// Score the frames at `indices` with `model`, from feature scores imported with [`Vmaf::import_feature_score`] rather than from pictures.
+    /// This re-fuses features which were already extracted, such as from an earlier run, without decoding any video
+    pub fn get_imported_scores(
+        mut self,
+        model: Model,
+        indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Vec<FrameScore>, VmafError> {
+        let scores = indices
+            .into_iter()
+            .map(|index| {
+                self.get_score_at_index(&model, index)
+                    .change_context(VmafError::GetScore(index))
+                    .map(|score| FrameScore::new(index, score))
+            })
+            .collect::<Result<Vec<FrameScore>, VmafError>>()?;
+
+        self.write_output_file()?;
+
+        Ok(scores)
+    }
+
+    /// Write libvmaf's report to the file given to [`Vmaf::with_output`], if any
+    fn write_output_file(&mut self) -> Result<(), VmafError> {
+        let Some((path, format)) = self.output.clone() else {
+            return Ok(());
+        };
+
+        let error = || VmafError::Output(path.clone());
+
+        let c_path = CString::new(path.as_os_str().as_encoded_bytes())
+            .into_report()
+            .change_context_lazy(error)?;
+
+        self.write_output(&c_path, format)
+            .change_context_lazy(error)
+    }
+
+    /// Returns true if libvmaf extracts features from the frame pair at `index`.
+    /// When subsampling, only every `n_subsample`th frame pair is extracted, starting with the first
+    pub fn is_scored(&self, index: u32) -> bool {
+        self.config.n_subsample <= 1 || index % self.config.n_subsample == 0
+    }
+
     /// Use this function to get a vector of vmaf scores.
     ///
+    /// Scores are returned in decode order, one for every frame pair libvmaf extracted features from.
+    /// If this context subsamples, frames which were skipped are left out, so use [`FrameScore::index`] rather than the position
+    /// of a score in the vector to find the frame it belongs to.
+    ///
     /// To implement `TryInto` for Picture, you may use [`Picture.IntoRaw()`](../picture/struct.Picture.html#impl-IntoRaw-for-Picture) to get a `*mut VmafPicture`.
     /// Fill the data property of the VmafPicture raw pointer with pixel data. View [`impl TryFrom<VideoFrame> for Picture`](../picture/struct.Picture.html#impl-TryFrom<Video>-for-Picture)
     /// for reference.
     ///
     /// If you don't need a custom type for this, just use [`Video`](../video/struct.Video.html).
+    ///
+    /// Every score carries the presentation timestamp of its reference frame, as set on the `Picture` with [`Picture::set_pts()`].
+    /// Use [`Vmaf::with_time_base`] to have it converted to a duration as well
+    ///
+    /// If this context was given a [`CancelToken`] which is cancelled while frame pairs are being read, the frame pairs read so far are
+    /// scored and returned in [`VmafError::Cancelled`]
     pub fn get_vmaf_scores<
-        I: GetResolution
+        R: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>,
+        D: GetResolution
             + ExactSizeIterator
             + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>,
-        F: Fn(VmafStatus) -> (),
+        F: FnMut(VmafStatus),
     >(
         mut self,
-        reference: I,
-        distorted: I,
+        reference: R,
+        distorted: D,
         model: Model,
         callback: Option<F>,
-    ) -> Result<Vec<f64>, VmafError> {
+    ) -> Result<Vec<FrameScore>, VmafError> {
+        self.score_frames(reference, distorted, &model, callback)
+    }
+
+    /// Read every frame pair and score them, leaving the context open so that pooled scores may be retrieved afterwards
+    fn score_frames<
+        R: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>,
+        D: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>,
+        F: FnMut(VmafStatus),
+    >(
+        &mut self,
+        mut reference: R,
+        mut distorted: D,
+        model: &Model,
+        mut callback: Option<F>,
+    ) -> Result<Vec<FrameScore>, VmafError> {
         // Use features from model
-        self.use_features_from_model(&model)
+        self.use_features_from_model(model)
             .change_context(VmafError::Feature(model.version()))?;
 
         // Ensure videos have the same number of frames
@@ -93,79 +288,192 @@ impl Vmaf {
             )));
         }
 
-        let framepair = reference
-            .zip(distorted)
-            .map(
-                |(reference, distorted)| -> Result<(Picture, Picture), PictureError> {
-                    let reference_pic = TryInto::<Picture>::try_into(reference);
-                    let distorted_pic = TryInto::<Picture>::try_into(distorted);
-
-                    if let Some(callback) = &callback {
-                        callback(VmafStatus::Decode)
-                    }
-
-                    match (reference_pic, distorted_pic) {
-                        (Ok(reference), Ok(distorted)) => Ok((reference, distorted)),
-                        (Ok(_), Err(distortederr)) => Err(distortederr),
-                        (Err(referenceerr), Ok(_)) => Err(referenceerr),
-                        (Err(referr), Err(_)) => Err(referr),
-                    }
-                },
-            )
-            .enumerate()
-            .map(|(index, result)| match result {
-                Ok((reference, distorted)) => {
-                    match self.read_pictures(reference, distorted, index.try_into().unwrap()) {
-                        Ok(()) => Ok(index),
-                        Err(e) => Err(e).change_context(VmafError::Other),
-                    }
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("get_vmaf_scores", model = ?model.version(), frames = ref_frames).entered();
+
+        let start = Instant::now();
+        // An ExactSizeIterator may still not know how many frames it has, such as a `Video` whose container doesn't say
+        let total = (ref_frames > 0).then_some(ref_frames);
+
+        let cancel = self.cancel.clone();
+        let mut cancelled = false;
+
+        let mut framepair: Vec<(usize, Option<i64>)> = vec![];
+
+        for index in 0.. {
+            // Checked before the next frame pair is decoded, so that a frame pair is never left half read
+            if cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+                cancelled = true;
+                break;
+            }
+
+            // Sources such as `Video` decode in `next()`, so that is timed along with the conversion to pictures
+            #[cfg(feature = "tracing")]
+            let decode_start = Instant::now();
+
+            let (reference, distorted) = match (reference.next(), distorted.next()) {
+                (Some(reference), Some(distorted)) => (reference, distorted),
+                _ => break,
+            };
+
+            let reference_pic = TryInto::<Picture>::try_into(reference);
+            let distorted_pic = TryInto::<Picture>::try_into(distorted);
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(frame = index, elapsed = ?decode_start.elapsed(), "Decoded frame pair");
+
+            if let Some(callback) = &mut callback {
+                callback(VmafStatus::Decode {
+                    index: index.try_into().unwrap(),
+                    total,
+                    elapsed: start.elapsed(),
+                })
+            }
+
+            // Stops at the first frame pair which couldn't be read, rather than decoding the rest of the video first
+            let (reference, distorted) = match (reference_pic, distorted_pic) {
+                (Ok(reference), Ok(distorted)) => (reference, distorted),
+                (Ok(_), Err(distortederr)) => {
+                    return Err(distortederr.change_context(VmafError::Other))
                 }
-                Err(error) => Err(error.change_context(VmafError::Other)),
-            })
-            .collect::<Vec<Result<usize, VmafError>>>();
+                (Err(referenceerr), Ok(_)) => {
+                    return Err(referenceerr.change_context(VmafError::Other))
+                }
+                (Err(referr), Err(_)) => return Err(referr.change_context(VmafError::Other)),
+            };
+
+            let pts = reference.pts();
+
+            #[cfg(feature = "tracing")]
+            let extract_start = Instant::now();
+
+            self.run_extractors(&reference, &distorted, index.try_into().unwrap())?;
+
+            self.read_pictures(reference, distorted, index.try_into().unwrap())
+                .change_context(VmafError::Other)?;
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(frame = index, elapsed = ?extract_start.elapsed(), "Extracted features");
+
+            framepair.push((index, pts));
+        }
+
+        #[cfg(feature = "tracing")]
+        let flush_start = Instant::now();
 
         self.finish_reading_pictures()
             .change_context(VmafError::ClearFrame)?;
 
-        let mut scores: Vec<f64> = vec![];
+        #[cfg(feature = "tracing")]
+        tracing::debug!(elapsed = ?flush_start.elapsed(), "Flushed feature extractors");
 
-        for pairindex in framepair {
-            match pairindex {
-                Ok(index) => {
-                    if let Some(callback) = &callback {
-                        callback(VmafStatus::GetScore)
-                    }
+        if let Some(callback) = &mut callback {
+            callback(VmafStatus::Flush {
+                elapsed: start.elapsed(),
+            })
+        }
 
-                    let score = self
-                        .get_score_at_index(&model, index.try_into().unwrap())
-                        .change_context(VmafError::GetScore(index.try_into().unwrap()))?;
-                    scores.push(score);
-                }
-                Err(e) => bail!(e),
+        let to_score = framepair
+            .iter()
+            .filter(|(index, _)| self.is_scored((*index).try_into().unwrap()))
+            .count();
+
+        // Saved before any frame is scored, so that the scores of the model aren't mistaken for features
+        if let Some(cache) = self.feature_cache.clone().filter(|_| !cancelled) {
+            let indices: Vec<u32> = framepair
+                .iter()
+                .map(|(index, _)| (*index).try_into().unwrap())
+                .filter(|index| self.is_scored(*index))
+                .collect();
+            self.save_feature_scores(&cache, &indices)
+                .change_context(VmafError::FeatureCache(cache.path().to_path_buf()))?;
+        }
+
+        let mut scores: Vec<FrameScore> = vec![];
+
+        for (index, pts) in framepair {
+            let index: u32 = index.try_into().unwrap();
+
+            // Frames skipped by subsampling have no score
+            if !self.is_scored(index) {
+                continue;
             }
+
+            #[cfg(feature = "tracing")]
+            let score_start = Instant::now();
+
+            let score = self
+                .get_score_at_index(model, index)
+                .change_context(VmafError::GetScore(index))?;
+
+            #[cfg(feature = "tracing")]
+            tracing::trace!(frame = index, score, elapsed = ?score_start.elapsed(), "Scored frame");
+
+            let timestamp = pts
+                .zip(self.time_base)
+                .and_then(|(pts, (time_base, start))| {
+                    to_duration(pts.checked_sub(start)?, time_base)
+                });
+
+            if let Some(callback) = &mut callback {
+                callback(VmafStatus::GetScore {
+                    index,
+                    total: Some(to_score),
+                    score,
+                    pts,
+                    timestamp,
+                    elapsed: start.elapsed(),
+                })
+            }
+
+            scores.push(FrameScore {
+                index,
+                score,
+                pts,
+                timestamp,
+            });
+        }
+
+        if cancelled {
+            #[cfg(feature = "tracing")]
+            tracing::info!(scored = scores.len(), elapsed = ?start.elapsed(), "Cancelled");
+
+            return Err(Report::new(VmafError::Cancelled(scores)));
+        }
+
+        self.write_output_file()?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(scored = scores.len(), elapsed = ?start.elapsed(), "Finished scoring");
+
+        if let Some(callback) = &mut callback {
+            callback(VmafStatus::Done {
+                scored: scores.len(),
+                elapsed: start.elapsed(),
+            })
         }
 
         Ok(scores)
     }
 }
 
+// A VmafContext has no affinity to the thread that created it. Its thread pool and feature collector
+// synchronize internally, but concurrent calls into the same context aren't safe, so Vmaf is Send but not Sync
+unsafe impl Send for Vmaf {}
+
 impl Default for Vmaf {
     fn default() -> Self {
-        Self::new(
-            VmafLogLevel::VMAF_LOG_LEVEL_WARNING,
-            num_cpus::get().try_into().unwrap(),
-            0,
-            0,
-        )
-        .expect("Couldn't construct default Vmaf context")
+        VmafConfig::default()
+            .build()
+            .expect("Couldn't construct default Vmaf context")
     }
 }
 
 impl Drop for Vmaf {
     fn drop(&mut self) {
         unsafe {
-            assert!(!self.0.is_null());
-            let err = vmaf_close(self.0);
+            assert!(!self.ctx.is_null());
+            let err = vmaf_close(self.ctx);
             FFIError::check_err(err)
                 .attach_printable("Encountered error when dropping VmafContext")
                 .unwrap();
@@ -177,13 +485,13 @@ impl Deref for Vmaf {
     type Target = *mut VmafContext;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.ctx
     }
 }
 
 impl DerefMut for Vmaf {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.ctx
     }
 }
 #[cfg(test)]
@@ -193,7 +501,7 @@ mod test {
         video::Video,
     };
 
-    use super::{Vmaf, VmafStatus};
+    use super::{cancel::CancelToken, error::VmafError, output::OutputFormat, Vmaf, VmafStatus};
     use libvmaf_sys::VmafLogLevel;
 
     #[test]
@@ -218,13 +526,143 @@ mod test {
         let config = ModelConfig::default();
         let _model: Model = Model::new(config, "vmaf_v0.6.1".to_string()).unwrap();
 
+        let mut decoded = 0;
+        let mut flushed = 0;
+        let mut scored = 0;
+        let mut done = false;
+
         let x = |x: VmafStatus| match x {
-            VmafStatus::Decode => println!("{x:?}"),
-            VmafStatus::GetScore => println!("{x:?}"),
+            VmafStatus::Decode { .. } => decoded += 1,
+            VmafStatus::Flush { .. } => flushed += 1,
+            VmafStatus::GetScore { .. } => scored += 1,
+            VmafStatus::Done { .. } => done = true,
+        };
+
+        let scores = _vmaf
+            .with_time_base(&reference)
+            .get_vmaf_scores(reference, distorted, _model, Some(x))
+            .unwrap();
+
+        assert_eq!(decoded, scores.len());
+        assert_eq!(flushed, 1);
+        assert_eq!(scored, scores.len());
+        assert!(done);
+
+        // Timestamps come from the reference's presentation timestamps
+        assert!(scores.iter().all(|frame| frame.timestamp.is_some()));
+        assert!(scores
+            .windows(2)
+            .all(|pair| pair[0].timestamp < pair[1].timestamp));
+    }
+
+    #[test]
+    fn send() {
+        fn assert_send<T: Send>() {}
+
+        assert_send::<Vmaf>();
+        assert_send::<Model>();
+        assert_send::<crate::picture::Picture>();
+        assert_send::<Video>();
+    }
+
+    #[test]
+    fn cancel() {
+        let token = CancelToken::new();
+        let _vmaf = Vmaf::default().with_cancel_token(token.clone());
+
+        let reference: Video = Video::new(&"./video/Big Buck Bunny 720P.m4v", 640, 360).unwrap();
+        let distorted: Video = Video::new(&"./video/Big Buck Bunny 720P.m4v", 640, 360).unwrap();
+        let config = ModelConfig::default();
+        let _model: Model = Model::new(config, "vmaf_v0.6.1".to_string()).unwrap();
+
+        let x = |x: VmafStatus| {
+            if let VmafStatus::Decode { index: 9, .. } = x {
+                token.cancel()
+            }
         };
 
-        _vmaf
+        let err = _vmaf
             .get_vmaf_scores(reference, distorted, _model, Some(x))
+            .unwrap_err();
+
+        let scores = err.current_context().partial_scores().unwrap();
+        assert_eq!(scores.len(), 10);
+    }
+
+    #[test]
+    fn import_feature_score() {
+        let mut vmaf = Vmaf::default();
+        let model: Model = Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+
+        let features = [
+            ("VMAF_integer_feature_adm2_score", 0.95),
+            ("VMAF_integer_feature_motion2_score", 3.0),
+            ("VMAF_integer_feature_vif_scale0_score", 0.6),
+            ("VMAF_integer_feature_vif_scale1_score", 0.9),
+            ("VMAF_integer_feature_vif_scale2_score", 0.95),
+            ("VMAF_integer_feature_vif_scale3_score", 0.97),
+        ];
+
+        for index in 0..3 {
+            for (name, value) in features {
+                vmaf.import_feature_score(name, index, value).unwrap();
+            }
+        }
+
+        // Only one score per feature and frame
+        assert!(vmaf
+            .import_feature_score("VMAF_integer_feature_adm2_score", 0, 0.5)
+            .is_err());
+
+        let err = vmaf.import_feature_score("adm\0", 0, 0.5).unwrap_err();
+        assert!(matches!(
+            err.current_context(),
+            VmafError::Import { index: 0, .. }
+        ));
+        assert!(err.contains::<std::ffi::NulError>());
+
+        let scores = vmaf.get_imported_scores(model, 0..3).unwrap();
+        assert_eq!(scores.len(), 3);
+        assert!(scores
+            .iter()
+            .all(|frame| (0.0..=100.0).contains(&frame.score)));
+        assert_eq!(scores[0].score, scores[2].score);
+    }
+
+    #[test]
+    fn output_path() {
+        let model: Model = Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+
+        let err = Vmaf::default()
+            .with_output("report\0.json", OutputFormat::Json)
+            .get_imported_scores(model, [])
+            .unwrap_err();
+
+        assert!(matches!(err.current_context(), VmafError::Output(_)));
+        assert!(err.contains::<std::ffi::NulError>());
+    }
+
+    #[test]
+    fn subsample() {
+        let _vmaf = Vmaf::new(
+            VmafLogLevel::VMAF_LOG_LEVEL_DEBUG,
+            num_cpus::get().try_into().unwrap(),
+            5,
+            0,
+        )
+        .expect("Recieved error code from constructor");
+
+        let reference: Video = Video::new(&"./video/Big Buck Bunny 720P.m4v", 640, 360).unwrap();
+        let distorted: Video = Video::new(&"./video/Big Buck Bunny 720P.m4v", 640, 360).unwrap();
+        let num_frames = reference.len();
+        let config = ModelConfig::default();
+        let _model: Model = Model::new(config, "vmaf_v0.6.1".to_string()).unwrap();
+
+        let scores = _vmaf
+            .get_vmaf_scores(reference, distorted, _model, None::<fn(VmafStatus)>)
             .unwrap();
+
+        assert_eq!(scores.len(), (num_frames + 4) / 5);
+        assert!(scores.iter().all(|frame| frame.index % 5 == 0));
     }
 }
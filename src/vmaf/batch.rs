@@ -0,0 +1,308 @@
+use error_stack::{Report, Result};
+use ffmpeg_next::Rational;
+use libvmaf_sys::VmafPixelFormat;
+use std::{
+    sync::{
+        mpsc::{sync_channel, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+use crate::{
+    model::Model,
+    picture::{error::PictureError, Picture, ValidRef},
+    video::{
+        resolution::{GetResolution, Resolution},
+        timebase::GetTimeBase,
+    },
+};
+
+use super::{config::VmafConfig, error::VmafError, score::FrameScore, status::VmafStatus};
+
+/// Scores many distorted inputs against a single reference, such as every rendition of an encoding ladder
+///
+/// The reference is decoded and converted once, and the planes of each frame are shared by one `Vmaf` context per distorted input,
+/// which copies them into a picture of its own.
+/// Every context runs on its own thread, decoding its distorted input as it goes,
+/// and the threads libvmaf uses for feature extraction are split evenly between the contexts
+/// ```ignore
+/// let reports = Batch::new(VmafConfig::new().n_threads(16)).score(
+///     Video::new(&"reference.mkv", 1920, 1080)?,
+///     vec![(Video::new(&"1080p.mkv", 1920, 1080)?, model_1080), (Video::new(&"720p.mkv", 1920, 1080)?, model_720)],
+///     None::<fn(usize, VmafStatus)>,
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Batch {
+    config: VmafConfig,
+    buffer: usize,
+    time_base: Option<(Rational, i64)>,
+}
+
+/// A reference frame converted once by a [`Batch`]
+struct SharedPicture {
+    /// Every row of every plane, without padding
+    data: Vec<u8>,
+    pix_fmt: VmafPixelFormat,
+    bits_per_channel: u32,
+    width: u32,
+    height: u32,
+    pts: Option<i64>,
+}
+
+impl SharedPicture {
+    fn new(picture: &Picture) -> SharedPicture {
+        let mut data = vec![];
+        picture.extend_with_planes(&mut data);
+
+        SharedPicture {
+            data,
+            pix_fmt: picture.pix_fmt(),
+            bits_per_channel: picture.bits_per_channel(),
+            width: picture.plane_width(0) as u32,
+            height: picture.plane_height(0) as u32,
+            pts: picture.pts(),
+        }
+    }
+}
+
+/// A reference frame as received by a context, or why it couldn't be converted
+#[derive(Clone)]
+struct SharedFrame(std::result::Result<Arc<SharedPicture>, Arc<str>>);
+
+impl TryFrom<SharedFrame> for Picture {
+    type Error = Report<PictureError>;
+
+    fn try_from(frame: SharedFrame) -> core::result::Result<Self, Self::Error> {
+        let shared = frame
+            .0
+            .map_err(|reason| Report::new(PictureError::Construct).attach_printable(reason))?;
+
+        let mut picture = Picture::new(
+            shared.pix_fmt,
+            shared.bits_per_channel,
+            shared.width,
+            shared.height,
+        )?;
+        picture.set_pts(shared.pts);
+        picture.fill_planes(&shared.data);
+
+        Ok(picture)
+    }
+}
+
+/// Reference frames sent to a single context of a [`Batch`]
+struct ChannelFrames {
+    receiver: Receiver<SharedFrame>,
+    resolution: Resolution,
+    time_base: Option<(Rational, i64)>,
+    remaining: usize,
+}
+
+impl GetResolution for ChannelFrames {
+    fn get_resolution(&self) -> &Resolution {
+        &self.resolution
+    }
+}
+
+impl GetTimeBase for ChannelFrames {
+    fn get_time_base(&self) -> Option<Rational> {
+        self.time_base.map(|(time_base, _)| time_base)
+    }
+
+    fn get_start_time(&self) -> i64 {
+        self.time_base.map_or(0, |(_, start_time)| start_time)
+    }
+}
+
+impl Iterator for ChannelFrames {
+    type Item = SharedFrame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.receiver.recv().ok()?;
+        self.remaining = self.remaining.saturating_sub(1);
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for ChannelFrames {}
+
+impl Batch {
+    /// `config` is used for every context, except that its `n_threads` is the thread budget for the whole batch
+    pub fn new(config: VmafConfig) -> Batch {
+        Batch {
+            config,
+            buffer: 8,
+            time_base: None,
+        }
+    }
+
+    /// Number of decoded reference frames which may be queued up for each context. Defaults to 8.
+    /// The reference is only decoded as fast as the slowest context consumes it
+    pub fn buffer(mut self, frames: usize) -> Batch {
+        self.buffer = frames;
+        self
+    }
+
+    /// Have the scores of every rendition carry timestamps, converted with the time base of `reference`.
+    /// See [`Vmaf::with_time_base`](super::Vmaf::with_time_base)
+    pub fn with_time_base(mut self, reference: &impl GetTimeBase) -> Batch {
+        self.time_base = reference
+            .get_time_base()
+            .map(|time_base| (time_base, reference.get_start_time()));
+        self
+    }
+
+    /// Score every distorted input in `renditions` against `reference`, each with the model it is paired with.
+    ///
+    /// Returns one report per rendition, in the order they were given. A rendition which fails doesn't stop the others.
+    /// `callback` receives the status updates of every context, along with the index of the rendition they belong to
+    pub fn score<P, Q, R, D, F>(
+        &self,
+        reference: R,
+        renditions: Vec<(D, Model)>,
+        callback: Option<F>,
+    ) -> Vec<Result<Vec<FrameScore>, VmafError>>
+    where
+        P: TryInto<Picture<ValidRef>, Error = Report<PictureError>>,
+        R: GetResolution + ExactSizeIterator + Iterator<Item = P>,
+        Q: TryInto<Picture<ValidRef>, Error = Report<PictureError>>,
+        D: GetResolution + ExactSizeIterator + Iterator<Item = Q> + Send,
+        F: Fn(usize, VmafStatus) + Sync,
+    {
+        if renditions.is_empty() {
+            return vec![];
+        }
+
+        let resolution = reference.get_resolution().clone();
+        let total = reference.len();
+
+        let contexts: u32 = renditions.len().try_into().unwrap_or(u32::MAX);
+        let config = self
+            .config
+            .n_threads((self.config.get_n_threads() / contexts).max(1));
+        let callback = callback.as_ref();
+
+        thread::scope(|scope| {
+            let mut senders = vec![];
+            let mut workers = vec![];
+
+            for (rendition, (distorted, model)) in renditions.into_iter().enumerate() {
+                let (sender, receiver) = sync_channel(self.buffer);
+                let frames = ChannelFrames {
+                    receiver,
+                    resolution: resolution.clone(),
+                    time_base: self.time_base,
+                    remaining: total,
+                };
+
+                workers.push(scope.spawn(move || -> Result<Vec<FrameScore>, VmafError> {
+                    let vmaf = config.build()?.with_time_base(&frames);
+                    let callback = callback
+                        .map(|callback| move |status: VmafStatus| callback(rendition, status));
+
+                    vmaf.get_vmaf_scores(frames, distorted, model, callback)
+                }));
+                senders.push(sender);
+            }
+
+            for frame in reference {
+                // A frame which can't be converted fails every context when they get to it
+                let frame = SharedFrame(match TryInto::<Picture>::try_into(frame) {
+                    Ok(picture) => Ok(Arc::new(SharedPicture::new(&picture))),
+                    Err(e) => Err(Arc::from(format!("{e:?}"))),
+                });
+
+                // A context which has failed hangs up, the rest carry on
+                senders.retain(|sender| sender.send(frame.clone()).is_ok());
+
+                if senders.is_empty() {
+                    break;
+                }
+            }
+
+            // Hang up so that every context sees the end of the reference
+            drop(senders);
+
+            workers
+                .into_iter()
+                .map(|worker| match worker.join() {
+                    Ok(report) => report,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Batch;
+    use crate::{
+        model::{config::ModelConfig, Model},
+        video::fixture,
+        vmaf::{config::VmafConfig, pooling::PoolingMethod, status::VmafStatus},
+    };
+
+    #[test]
+    fn score() {
+        let model = || Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+
+        let reference = fixture::raw_video(5, 0);
+        let distorted = [fixture::raw_video(5, 4), fixture::raw_video(5, 16)];
+
+        let renditions = distorted
+            .iter()
+            .map(|file| (fixture::open(file), model()))
+            .collect();
+
+        let reports = Batch::new(VmafConfig::new())
+            .with_time_base(&fixture::open(&reference))
+            .score(
+                fixture::open(&reference),
+                renditions,
+                None::<fn(usize, VmafStatus)>,
+            );
+
+        assert_eq!(reports.len(), 2);
+
+        // Every rendition scores the same as it would on its own
+        for (report, file) in reports.iter().zip(&distorted) {
+            let batched = report.as_ref().unwrap();
+
+            let reference = fixture::open(&reference);
+            let alone = VmafConfig::new()
+                .build()
+                .unwrap()
+                .with_time_base(&reference)
+                .get_vmaf_scores(
+                    reference,
+                    fixture::open(file),
+                    model(),
+                    None::<fn(VmafStatus)>,
+                )
+                .unwrap();
+
+            assert_eq!(batched.len(), alone.len());
+            for (batched, alone) in batched.iter().zip(&alone) {
+                assert_eq!(
+                    (batched.index, batched.pts, batched.timestamp),
+                    (alone.index, alone.pts, alone.timestamp)
+                );
+                assert!((batched.score - alone.score).abs() < 1e-9);
+            }
+            assert!(batched.iter().all(|frame| frame.timestamp.is_some()));
+        }
+
+        let means: Vec<f64> = reports
+            .iter()
+            .map(|report| PoolingMethod::Mean.pool(report.as_ref().unwrap()).unwrap())
+            .collect();
+        assert!(means[0] > means[1]);
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag which stops a running `Vmaf::get_vmaf_scores()` from another thread
+///
+/// The flag is checked before every frame pair is decoded. Once it is set, libvmaf's feature extractors are flushed and
+/// `get_vmaf_scores()` returns [`VmafError::Cancelled`](super::error::VmafError::Cancelled) with the scores of every frame pair read so far
+/// ```ignore
+/// let token = CancelToken::new();
+/// let vmaf = Vmaf::default().with_cancel_token(token.clone());
+/// // Elsewhere
+/// token.cancel();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancelToken {
+    /// Use an existing atomic flag as a cancel token. Setting the flag to `true` cancels
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        CancelToken(flag)
+    }
+}
@@ -0,0 +1,236 @@
+use bitflags::bitflags;
+use error_stack::{Report, Result, ResultExt};
+use libvmaf_sys::VmafLogLevel;
+
+use super::{
+    error::{ConfigError, VmafError},
+    Vmaf,
+};
+
+bitflags! {
+    /// SIMD instruction sets libvmaf may use for feature extraction.
+    ///
+    /// These are the bits of libvmaf's `cpumask`, which lists the instruction sets that should *not* be used.
+    /// Disabling them is a routine way of finding out whether a difference in scores comes from SIMD or scalar code paths
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct CpuFeatures: u64 {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        const SSE2 = 1 << 0;
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        const SSSE3 = 1 << 1;
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        const SSE41 = 1 << 2;
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        const AVX2 = 1 << 3;
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        const AVX512 = 1 << 4;
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        const AVX512ICL = 1 << 5;
+        #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+        const NEON = 1 << 0;
+    }
+}
+
+/// Every instruction set, ordered from oldest to newest. Each instruction set builds on the ones before it
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const HIERARCHY: &[CpuFeatures] = &[
+    CpuFeatures::SSE2,
+    CpuFeatures::SSSE3,
+    CpuFeatures::SSE41,
+    CpuFeatures::AVX2,
+    CpuFeatures::AVX512,
+    CpuFeatures::AVX512ICL,
+];
+#[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+const HIERARCHY: &[CpuFeatures] = &[CpuFeatures::NEON];
+#[cfg(not(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64"
+)))]
+const HIERARCHY: &[CpuFeatures] = &[];
+
+impl CpuFeatures {
+    /// These instruction sets, plus every newer instruction set that builds on them.
+    ///
+    /// `CpuFeatures::AVX2.and_above()` disables everything from AVX2 upwards
+    pub fn and_above(self) -> CpuFeatures {
+        match HIERARCHY.iter().position(|feature| self.contains(*feature)) {
+            Some(oldest) => HIERARCHY[oldest..]
+                .iter()
+                .fold(self, |features, feature| features | *feature),
+            None => self,
+        }
+    }
+}
+
+/// The most threads [`VmafConfig::n_threads`] accepts. libvmaf starts every thread up front, so far more than there are cpus only wastes memory
+pub const MAX_THREADS: u32 = 1024;
+
+/// A builder for [`Vmaf`] contexts
+///
+/// The configuration is validated before it's handed to `vmaf_init`
+/// ```ignore
+/// let vmaf = VmafConfig::new()
+///     .n_threads(4)
+///     .disable_cpu_features(CpuFeatures::AVX2.and_above())
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct VmafConfig {
+    log_level: VmafLogLevel,
+    n_threads: u32,
+    n_subsample: u32,
+    disabled_cpu_features: CpuFeatures,
+}
+
+impl VmafConfig {
+    /// Warning log level, a thread per logical cpu, no subsampling and every instruction set enabled
+    pub fn new() -> VmafConfig {
+        VmafConfig {
+            log_level: VmafLogLevel::VMAF_LOG_LEVEL_WARNING,
+            n_threads: num_cpus::get()
+                .try_into()
+                .unwrap_or(MAX_THREADS)
+                .min(MAX_THREADS),
+            n_subsample: 1,
+            disabled_cpu_features: CpuFeatures::empty(),
+        }
+    }
+
+    pub fn log_level(mut self, log_level: VmafLogLevel) -> VmafConfig {
+        self.log_level = log_level;
+        self
+    }
+
+    /// Match libvmaf's log level to the verbosity of the current `tracing` subscriber
+    #[cfg(feature = "tracing")]
+    pub fn log_level_from_tracing(self) -> VmafConfig {
+        self.log_level(super::log::from_level_filter(
+            tracing::level_filters::LevelFilter::current(),
+        ))
+    }
+
+    /// Number of threads libvmaf uses for feature extraction, at most [`MAX_THREADS`]. 0 extracts features on the calling thread
+    pub fn n_threads(mut self, n_threads: u32) -> VmafConfig {
+        self.n_threads = n_threads;
+        self
+    }
+
+    /// Only extract features from every `n_subsample`th frame pair. 1, the default, scores every frame and 0 is rejected
+    pub fn n_subsample(mut self, n_subsample: u32) -> VmafConfig {
+        self.n_subsample = n_subsample;
+        self
+    }
+
+    /// Stop libvmaf from using these instruction sets. Calling this more than once adds to the set of disabled instruction sets
+    pub fn disable_cpu_features(mut self, features: CpuFeatures) -> VmafConfig {
+        self.disabled_cpu_features |= features;
+        self
+    }
+
+    /// Disable every SIMD instruction set, so that only scalar code paths are used
+    pub fn scalar_only(self) -> VmafConfig {
+        self.disable_cpu_features(CpuFeatures::all())
+    }
+
+    pub fn get_log_level(&self) -> VmafLogLevel {
+        self.log_level
+    }
+
+    pub fn get_n_threads(&self) -> u32 {
+        self.n_threads
+    }
+
+    pub fn get_n_subsample(&self) -> u32 {
+        self.n_subsample
+    }
+
+    pub fn get_disabled_cpu_features(&self) -> CpuFeatures {
+        self.disabled_cpu_features
+    }
+
+    /// Check this configuration for options libvmaf can't honour, such as subsampling by 0 or more than [`MAX_THREADS`] threads.
+    ///
+    /// Instruction sets may be disabled in any combination, although newer ones generally build on older ones.
+    /// See [`CpuFeatures::and_above`] to disable an instruction set along with everything newer
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.n_subsample == 0 {
+            return Err(Report::new(ConfigError::Subsample));
+        }
+
+        if self.n_threads > MAX_THREADS {
+            return Err(Report::new(ConfigError::Threads(self.n_threads)));
+        }
+
+        let unknown = self.disabled_cpu_features.bits() & !CpuFeatures::all().bits();
+
+        if unknown != 0 {
+            return Err(Report::new(ConfigError::CpuFeatures(unknown)));
+        }
+
+        Ok(())
+    }
+
+    /// Validate this configuration and construct a [`Vmaf`] context from it
+    pub fn build(self) -> Result<Vmaf, VmafError> {
+        self.validate().change_context(VmafError::Construct)?;
+
+        Vmaf::new(
+            self.log_level,
+            self.n_threads,
+            self.n_subsample,
+            self.disabled_cpu_features.bits(),
+        )
+    }
+}
+
+impl Default for VmafConfig {
+    fn default() -> Self {
+        VmafConfig::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CpuFeatures, VmafConfig, MAX_THREADS};
+
+    #[test]
+    fn build() {
+        assert!(VmafConfig::new().n_subsample(0).build().is_err());
+        assert!(VmafConfig::new().n_threads(u32::MAX).build().is_err());
+
+        let _vmaf = VmafConfig::new()
+            .n_threads(1)
+            .n_subsample(2)
+            .scalar_only()
+            .build()
+            .expect("Recieved error code from constructor");
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn validate() {
+        let config = VmafConfig::new().disable_cpu_features(CpuFeatures::AVX512);
+        assert!(config.validate().is_ok());
+
+        let config = VmafConfig::new().disable_cpu_features(CpuFeatures::AVX2.and_above());
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.get_disabled_cpu_features(),
+            CpuFeatures::AVX2 | CpuFeatures::AVX512 | CpuFeatures::AVX512ICL
+        );
+
+        assert!(VmafConfig::new().scalar_only().validate().is_ok());
+        assert!(VmafConfig::new().n_threads(MAX_THREADS).validate().is_ok());
+        assert!(VmafConfig::new().n_subsample(0).validate().is_err());
+        assert!(VmafConfig::new()
+            .n_threads(MAX_THREADS + 1)
+            .validate()
+            .is_err());
+
+        let config = VmafConfig::new().disable_cpu_features(CpuFeatures::from_bits_retain(1 << 40));
+        assert!(config.validate().is_err());
+    }
+}
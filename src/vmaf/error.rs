@@ -1,8 +1,11 @@
 use errno::Errno;
+use std::path::PathBuf;
 use thiserror::Error;
 
 use crate::video::resolution::Resolution;
 
+use super::{score::FrameScore, segment::Segment};
+
 /// An enum of every possible error calculating a Vmaf Score
 #[derive(Error, Debug)]
 pub enum VmafError {
@@ -27,7 +30,84 @@ pub enum VmafError {
     /// The two `Video`'s provided had mismatching resolutions
     #[error("Mismatched resolutions: Reference: {0} Distorted: {1}")]
     Resolution(Resolution, Resolution),
+    /// Score calculation was stopped by a [`CancelToken`](super::cancel::CancelToken).
+    /// Holds the scores of every frame pair which was read before cancellation
+    #[error("Cancelled after scoring {} frames", .0.len())]
+    Cancelled(Vec<FrameScore>),
+    /// A segment given to `Vmaf::get_segment_scores()` was empty, went past the last frame, or couldn't be pooled
+    #[error("Couldn't pool scores of segment {0}")]
+    Segment(Segment),
+    /// A feature score couldn't be imported, because the name isn't valid or the frame already has a score for it
+    #[error("Couldn't import score of feature {name:?} for frame #{index}")]
+    Import { name: String, index: u32 },
+    /// Feature scores couldn't be saved to the path given to `Vmaf::with_feature_cache()`
+    #[error("Couldn't save feature scores to {0:?}")]
+    FeatureCache(PathBuf),
+    /// A [`FeatureExtractor`](super::extractor::FeatureExtractor) given to `Vmaf::with_extractor()` failed
+    #[error("Feature extractor {0:?} failed")]
+    Extractor(String),
+    /// libvmaf's report couldn't be written to the given path
+    #[error("Couldn't write output to {0:?}")]
+    Output(PathBuf),
     /// Something else went wrong when computing VMAF scores
     #[error("Couldn't run VMAF")]
     Other,
 }
+
+impl VmafError {
+    /// The scores computed before cancellation, if this is [`VmafError::Cancelled`]
+    pub fn partial_scores(&self) -> Option<&[FrameScore]> {
+        match self {
+            VmafError::Cancelled(scores) => Some(scores),
+            _ => None,
+        }
+    }
+}
+
+/// A [`VmafConfig`](super::config::VmafConfig) which was rejected before it reached `vmaf_init`
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    /// Bits of the cpumask which aren't an instruction set libvmaf knows on this architecture
+    #[error("Unknown instruction sets {0:#x}")]
+    CpuFeatures(u64),
+    /// Subsampling by 0 would skip every frame
+    #[error("n_subsample has to be at least 1")]
+    Subsample,
+    /// More threads than [`MAX_THREADS`](super::config::MAX_THREADS)
+    #[error("{0} threads is more than libvmaf's thread pool can be given")]
+    Threads(u32),
+}
+
+/// Feature scores which couldn't be saved or loaded by [`FeatureScores`](super::features::FeatureScores)
+#[derive(Error, Debug)]
+pub enum FeatureCacheError {
+    /// The file couldn't be opened or read
+    #[error("Couldn't read feature scores from {0:?}")]
+    Read(PathBuf),
+    /// The file couldn't be created or written
+    #[error("Couldn't write feature scores to {0:?}")]
+    Write(PathBuf),
+    /// A line which isn't in the format [`FeatureScores::save`](super::features::FeatureScores::save) writes, counting from 1
+    #[error("Malformed feature scores on line {0}")]
+    Parse(usize),
+    /// The file ends before the header, key or feature names
+    #[error("Feature scores end before their header")]
+    Truncated,
+    /// The scores were extracted from other inputs
+    #[error("Feature scores are for inputs {found:016x}, expected {expected:016x}")]
+    Key { expected: u64, found: u64 },
+    /// A frame was added out of order, or with other features than the frames before it
+    #[error("Frame #{0} is out of order or doesn't have a score for every feature")]
+    Frame(u32),
+    /// libvmaf couldn't write out the names of the features it collected
+    #[error("Couldn't list the collected features")]
+    Collect,
+}
+
+/// A frame pair a [`FeatureExtractor`](super::extractor::FeatureExtractor) couldn't extract features from
+#[derive(Error, Debug)]
+pub enum ExtractorError {
+    /// `reason` says what went wrong. Further details may be attached to the `Report` with `attach_printable`
+    #[error("Couldn't extract features from frame #{index}: {reason}")]
+    Frame { index: u32, reason: String },
+}
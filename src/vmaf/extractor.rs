@@ -0,0 +1,225 @@
+use error_stack::{Result, ResultExt};
+
+use crate::picture::Picture;
+
+use super::{
+    error::{ExtractorError, VmafError},
+    Vmaf,
+};
+
+/// A feature extractor implemented in Rust, run by `Vmaf::get_vmaf_scores()` alongside libvmaf's own extractors
+///
+/// Every frame pair is handed to the extractor before libvmaf reads it, including frames skipped by subsampling,
+/// so extractors which compare neighbouring frames see every frame. The scores it returns are imported with
+/// [`Vmaf::import_feature_score`], so they appear in the report written by [`Vmaf::with_output`] and may be saved
+/// with a [`FeatureCache`](super::features::FeatureCache). Features named the way a model expects them are fused by that model
+/// ```ignore
+/// struct MeanDifference;
+///
+/// impl FeatureExtractor for MeanDifference {
+///     fn name(&self) -> &str {
+///         "mean_difference"
+///     }
+///
+///     fn extract(&mut self, reference: &Picture, distorted: &Picture, index: u32) -> Result<Vec<(String, f64)>, ExtractorError> {
+///         let difference = /* ... */;
+///         Ok(vec![("mean_difference".to_string(), difference)])
+///     }
+/// }
+///
+/// let vmaf = Vmaf::default().with_extractor(MeanDifference);
+/// ```
+pub trait FeatureExtractor {
+    /// Name of the extractor, which identifies it in errors
+    fn name(&self) -> &str;
+
+    /// Scores of the frame pair at `index`, by feature name. A frame can only have one score per feature
+    ///
+    /// Failures are reported with [`ExtractorError::Frame`] saying what went wrong, and may carry more detail with `Report::attach_printable`.
+    /// Any error stops scoring
+    fn extract(
+        &mut self,
+        reference: &Picture,
+        distorted: &Picture,
+        index: u32,
+    ) -> Result<Vec<(String, f64)>, ExtractorError>;
+}
+
+impl Vmaf {
+    /// Run `extractor` on every frame pair read by `get_vmaf_scores()`. May be given several times
+    pub fn with_extractor(mut self, extractor: impl FeatureExtractor + Send + 'static) -> Vmaf {
+        self.extractors.push(Box::new(extractor));
+        self
+    }
+
+    pub(super) fn run_extractors(
+        &mut self,
+        reference: &Picture,
+        distorted: &Picture,
+        index: u32,
+    ) -> Result<(), VmafError> {
+        let mut scores = vec![];
+
+        for extractor in &mut self.extractors {
+            let extracted = extractor
+                .extract(reference, distorted, index)
+                .change_context_lazy(|| VmafError::Extractor(extractor.name().to_string()))?;
+
+            scores.extend(extracted);
+        }
+
+        for (name, value) in scores {
+            self.import_feature_score(&name, index, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use error_stack::{Report, Result};
+
+    use super::FeatureExtractor;
+    use crate::{
+        model::{config::ModelConfig, Model},
+        picture::Picture,
+        video::fixture,
+        vmaf::{
+            error::{ExtractorError, VmafError},
+            features::{FeatureCache, FeatureScores},
+            status::VmafStatus,
+            Vmaf,
+        },
+    };
+
+    /// Mean absolute difference of the luma planes
+    struct MeanDifference;
+
+    impl FeatureExtractor for MeanDifference {
+        fn name(&self) -> &str {
+            "mean_difference"
+        }
+
+        fn extract(
+            &mut self,
+            reference: &Picture,
+            distorted: &Picture,
+            index: u32,
+        ) -> Result<Vec<(String, f64)>, ExtractorError> {
+            if reference.bytes_per_value() != 1 {
+                return Err(Report::new(ExtractorError::Frame {
+                    index,
+                    reason: "only 8 bit pictures are supported".to_string(),
+                }));
+            }
+
+            let mut sum = 0u64;
+            for y in 0..reference.plane_height(0) {
+                sum += reference
+                    .row(0, y)
+                    .iter()
+                    .zip(distorted.row(0, y))
+                    .map(|(r, d)| u64::from(r.abs_diff(*d)))
+                    .sum::<u64>();
+            }
+
+            let pixels = reference.plane_width(0) * reference.plane_height(0);
+            Ok(vec![(
+                "mean_difference".to_string(),
+                sum as f64 / pixels as f64,
+            )])
+        }
+    }
+
+    struct Failing;
+
+    impl FeatureExtractor for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn extract(
+            &mut self,
+            _reference: &Picture,
+            _distorted: &Picture,
+            index: u32,
+        ) -> Result<Vec<(String, f64)>, ExtractorError> {
+            Err(Report::new(ExtractorError::Frame {
+                index,
+                reason: "always fails".to_string(),
+            })
+            .attach_printable("Attached details"))
+        }
+    }
+
+    #[test]
+    fn extract() {
+        let model: Model = Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        let reference = fixture::raw_video(3, 0);
+        let distorted = fixture::raw_video(3, 4);
+
+        let scores = Vmaf::default()
+            .with_extractor(MeanDifference)
+            .with_feature_cache(FeatureCache::new(file.path(), 0))
+            .get_vmaf_scores(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model,
+                None::<fn(VmafStatus)>,
+            )
+            .unwrap();
+
+        // The distorted frames only differ by their noise pattern
+        let noise: u32 = (0..fixture::HEIGHT)
+            .flat_map(|y| (0..fixture::WIDTH).map(move |x| (x * 7 + y * 13) % 5))
+            .sum();
+        let expected = f64::from(noise) / f64::from(fixture::WIDTH * fixture::HEIGHT);
+
+        // Imported into libvmaf's feature collector, which the cache is saved from
+        let features = FeatureScores::load(file.path()).unwrap();
+        assert_eq!(features.indices(), [0, 1, 2]);
+        assert_eq!(scores.len(), 3);
+        for index in features.indices() {
+            let difference = features.get("mean_difference", *index).unwrap();
+            assert!((difference - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn failing_extractor() {
+        let (reference, distorted) = (fixture::raw_video(3, 0), fixture::raw_video(3, 4));
+        let model: Model = Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+
+        let mut decoded = 0;
+        let mut flushed = false;
+
+        let x = |x: VmafStatus| match x {
+            VmafStatus::Decode { .. } => decoded += 1,
+            VmafStatus::Flush { .. } => flushed = true,
+            _ => (),
+        };
+
+        let err = Vmaf::default()
+            .with_extractor(Failing)
+            .get_vmaf_scores(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model,
+                Some(x),
+            )
+            .unwrap_err();
+
+        assert!(matches!(err.current_context(), VmafError::Extractor(name) if name == "failing"));
+        assert!(matches!(
+            err.downcast_ref::<ExtractorError>(),
+            Some(ExtractorError::Frame { index: 0, .. })
+        ));
+
+        // Scoring stops at the first frame pair, without decoding the rest of the video
+        assert_eq!(decoded, 1);
+        assert!(!flushed);
+    }
+}
@@ -0,0 +1,503 @@
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use std::{
+    ffi::CString,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::model::Model;
+
+use super::{
+    error::{FeatureCacheError, VmafError},
+    output::OutputFormat,
+    score::FrameScore,
+    Vmaf,
+};
+
+const HEADER: &str = "# libvmaf-rs feature scores";
+
+/// Where and how [`Vmaf::with_feature_cache`] saves the feature scores of a run
+///
+/// Every feature libvmaf's feature collector has a score for is saved, whether it was extracted for the model, added with
+/// [`Vmaf::use_feature`] or imported by a [`FeatureExtractor`](super::extractor::FeatureExtractor).
+/// Features which weren't extracted for every scored frame are left out
+#[derive(Debug, Clone)]
+pub struct FeatureCache {
+    path: PathBuf,
+    key: u64,
+}
+
+impl FeatureCache {
+    /// Save feature scores to `path`, under `key`. `key` is usually [`FeatureScores::hash_inputs`] of the reference and distorted videos
+    pub fn new(path: impl AsRef<Path>, key: u64) -> FeatureCache {
+        FeatureCache {
+            path: path.as_ref().to_path_buf(),
+            key,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Per-frame feature scores from an earlier run, which can be fused by any model without decoding video again
+///
+/// Scores are saved as CSV, one row per scored frame and one column per feature, preceded by the key of the inputs they were extracted from.
+/// Values are written with as many digits as it takes to read them back exactly
+/// ```ignore
+/// let key = FeatureScores::hash_inputs(["reference.y4m", "distorted.mkv"])?;
+/// let scores = FeatureScores::load_keyed("features.csv", key)?;
+///
+/// let v4k = scores.rescore(Model::builtin(ModelConfig::default(), BuiltinModel::V4k0_6_1)?)?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureScores {
+    key: u64,
+    names: Vec<String>,
+    indices: Vec<u32>,
+    /// One row per frame in `indices`, one value per feature in `names`
+    values: Vec<Vec<f64>>,
+}
+
+impl FeatureScores {
+    /// No scores, under `key`
+    pub fn new(key: u64) -> FeatureScores {
+        FeatureScores {
+            key,
+            names: vec![],
+            indices: vec![],
+            values: vec![],
+        }
+    }
+
+    /// Hash of the contents of `paths`, in order. Files with the same contents have the same key, wherever they are
+    pub fn hash_inputs<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+    ) -> Result<u64, FeatureCacheError> {
+        // 64 bit FNV-1a, which is stable across platforms and Rust versions unlike `DefaultHasher`
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut buffer = vec![0u8; 1 << 16];
+
+        for path in paths {
+            let path = path.as_ref();
+            let error = || FeatureCacheError::Read(path.to_path_buf());
+
+            let mut file = File::open(path).into_report().change_context_lazy(error)?;
+
+            loop {
+                let read = file
+                    .read(&mut buffer)
+                    .into_report()
+                    .change_context_lazy(error)?;
+                if read == 0 {
+                    break;
+                }
+
+                for byte in &buffer[..read] {
+                    hash ^= u64::from(*byte);
+                    hash = hash.wrapping_mul(0x100000001b3);
+                }
+            }
+        }
+
+        Ok(hash)
+    }
+
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+
+    /// Names of the features, in the order they are saved
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Indices of the frames which have scores, in ascending order
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Score of the feature `name` for the frame at `index`
+    pub fn get(&self, name: &str, index: u32) -> Option<f64> {
+        let column = self.names.iter().position(|feature| feature == name)?;
+        let row = self.indices.binary_search(&index).ok()?;
+
+        self.values[row].get(column).copied()
+    }
+
+    /// Add the scores of a frame, one for each of `names`. Every frame has to have a score for the same features, and frames have to be added in ascending order
+    pub fn push(&mut self, index: u32, scores: &[(&str, f64)]) -> Result<(), FeatureCacheError> {
+        if self.indices.is_empty() {
+            self.names = scores.iter().map(|(name, _)| name.to_string()).collect();
+        }
+
+        let names_match = scores.len() == self.names.len()
+            && scores
+                .iter()
+                .zip(&self.names)
+                .all(|((name, _), expected)| name == expected);
+
+        if !names_match || self.indices.last().is_some_and(|last| *last >= index) {
+            return Err(Report::new(FeatureCacheError::Frame(index)));
+        }
+
+        self.indices.push(index);
+        self.values
+            .push(scores.iter().map(|(_, value)| *value).collect());
+
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), FeatureCacheError> {
+        let path = path.as_ref();
+        let error = || FeatureCacheError::Write(path.to_path_buf());
+
+        let file = File::create(path)
+            .into_report()
+            .change_context_lazy(error)?;
+        let mut writer = BufWriter::new(file);
+
+        let columns: Vec<&str> = std::iter::once("frame")
+            .chain(self.names.iter().map(String::as_str))
+            .collect();
+
+        writeln!(writer, "{HEADER}")
+            .and_then(|_| writeln!(writer, "# key={:016x}", self.key))
+            .and_then(|_| writeln!(writer, "{}", columns.join(",")))
+            .into_report()
+            .change_context_lazy(error)?;
+
+        for (index, values) in self.indices.iter().zip(&self.values) {
+            let row: Vec<String> = std::iter::once(index.to_string())
+                .chain(values.iter().map(f64::to_string))
+                .collect();
+            writeln!(writer, "{}", row.join(","))
+                .into_report()
+                .change_context_lazy(error)?;
+        }
+
+        writer.flush().into_report().change_context_lazy(error)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<FeatureScores, FeatureCacheError> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .into_report()
+            .change_context_lazy(|| FeatureCacheError::Read(path.to_path_buf()))?;
+
+        let mut lines = BufReader::new(file).lines().enumerate();
+        let mut next_line = |expected: &str| -> Result<(usize, String), FeatureCacheError> {
+            let (number, line) = lines
+                .next()
+                .ok_or_else(|| Report::new(FeatureCacheError::Truncated))
+                .attach_printable_lazy(|| format!("Expected {expected}"))?;
+
+            let line = line
+                .into_report()
+                .change_context_lazy(|| FeatureCacheError::Read(path.to_path_buf()))?;
+
+            Ok((number + 1, line))
+        };
+
+        let (number, header) = next_line("a header")?;
+        if header != HEADER {
+            return Err(Report::new(FeatureCacheError::Parse(number)));
+        }
+
+        let (number, key) = next_line("a key")?;
+        let key = key
+            .strip_prefix("# key=")
+            .and_then(|key| u64::from_str_radix(key, 16).ok())
+            .ok_or_else(|| Report::new(FeatureCacheError::Parse(number)))?;
+
+        let (number, columns) = next_line("feature names")?;
+        let mut columns = columns.split(',');
+        if columns.next() != Some("frame") {
+            return Err(Report::new(FeatureCacheError::Parse(number)));
+        }
+        let names: Vec<&str> = columns.collect();
+
+        let mut scores = FeatureScores::new(key);
+
+        for (number, line) in lines {
+            let number = number + 1;
+            let line = line
+                .into_report()
+                .change_context_lazy(|| FeatureCacheError::Read(path.to_path_buf()))?;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut fields = line.split(',');
+            let index = fields
+                .next()
+                .and_then(|index| index.parse::<u32>().ok())
+                .ok_or_else(|| Report::new(FeatureCacheError::Parse(number)))?;
+
+            let values = fields
+                .map(|value| value.parse::<f64>())
+                .collect::<std::result::Result<Vec<f64>, _>>()
+                .into_report()
+                .change_context_lazy(|| FeatureCacheError::Parse(number))?;
+
+            if values.len() != names.len() {
+                return Err(Report::new(FeatureCacheError::Parse(number)));
+            }
+
+            let row: Vec<(&str, f64)> = names.iter().copied().zip(values).collect();
+            scores
+                .push(index, &row)
+                .change_context(FeatureCacheError::Parse(number))?;
+        }
+
+        Ok(scores)
+    }
+
+    /// Load scores saved under `key`, failing with [`FeatureCacheError::Key`] if they were saved under another key
+    pub fn load_keyed(
+        path: impl AsRef<Path>,
+        key: u64,
+    ) -> Result<FeatureScores, FeatureCacheError> {
+        let scores = FeatureScores::load(path)?;
+
+        if scores.key != key {
+            return Err(Report::new(FeatureCacheError::Key {
+                expected: key,
+                found: scores.key,
+            }));
+        }
+
+        Ok(scores)
+    }
+
+    /// Fuse these scores with `model`, in a fresh `Vmaf` context
+    pub fn rescore(&self, model: Model) -> Result<Vec<FrameScore>, VmafError> {
+        let mut vmaf = Vmaf::default();
+        vmaf.import_features(self)?;
+        vmaf.get_imported_scores(model, self.indices.iter().copied())
+    }
+}
+
+impl Vmaf {
+    /// Have `get_vmaf_scores()` save the scores of the features in `cache` for every scored frame once scoring finishes.
+    /// Load them with [`FeatureScores::load`] to score them with another model without decoding video again
+    pub fn with_feature_cache(mut self, cache: FeatureCache) -> Vmaf {
+        self.feature_cache = Some(cache);
+        self
+    }
+
+    /// Import every score of `scores`. See [`Vmaf::import_feature_score`]
+    pub fn import_features(&mut self, scores: &FeatureScores) -> Result<(), VmafError> {
+        for (index, values) in scores.indices.iter().zip(&scores.values) {
+            for (name, value) in scores.names.iter().zip(values) {
+                self.import_feature_score(name, *index, *value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Names of the features libvmaf's feature collector has scores for.
+    /// libvmaf only lists them in its reports, where some are aliased, such as `integer_adm2` for `VMAF_integer_feature_adm2_score`,
+    /// so each is resolved to the name which has a score for the frame at `index`
+    fn collected_feature_names(&mut self, index: u32) -> Result<Vec<String>, FeatureCacheError> {
+        let report = tempfile::Builder::new()
+            .suffix(".csv")
+            .tempfile()
+            .into_report()
+            .change_context(FeatureCacheError::Collect)?;
+
+        let c_path = CString::new(report.path().as_os_str().as_encoded_bytes())
+            .into_report()
+            .change_context(FeatureCacheError::Collect)?;
+        self.write_output(&c_path, OutputFormat::Csv)
+            .change_context(FeatureCacheError::Collect)?;
+
+        // The first line is `Frame,` followed by every feature, each with a trailing comma
+        let mut header = String::new();
+        File::open(report.path())
+            .map(BufReader::new)
+            .and_then(|mut reader| reader.read_line(&mut header))
+            .into_report()
+            .change_context(FeatureCacheError::Collect)?;
+
+        let mut names: Vec<String> = vec![];
+        for alias in header.trim_end().split(',').skip(1) {
+            if alias.is_empty() {
+                continue;
+            }
+
+            let mut candidates = vec![alias.to_string(), format!("VMAF_feature_{alias}_score")];
+            if let Some(feature) = alias.strip_prefix("integer_") {
+                candidates.push(format!("VMAF_integer_feature_{feature}_score"));
+            }
+
+            let name = candidates.into_iter().find(|candidate| {
+                CString::new(candidate.as_str())
+                    .is_ok_and(|name| self.feature_score_at_index(&name, index).is_ok())
+            });
+
+            if let Some(name) = name.filter(|name| !names.contains(name)) {
+                names.push(name);
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Save the scores of every collected feature for the frames at `indices`.
+    /// Has to be called before any frame is scored, as libvmaf collects the scores of models alongside the features
+    pub(super) fn save_feature_scores(
+        &mut self,
+        cache: &FeatureCache,
+        indices: &[u32],
+    ) -> Result<(), FeatureCacheError> {
+        let names = match indices.first() {
+            Some(first) => self.collected_feature_names(*first)?,
+            None => vec![],
+        };
+
+        // One column per feature which has a score for every frame
+        let mut columns: Vec<(&str, Vec<f64>)> = vec![];
+        for name in &names {
+            let Ok(c_name) = CString::new(name.as_str()) else {
+                continue;
+            };
+
+            let values = indices
+                .iter()
+                .map(|index| self.feature_score_at_index(&c_name, *index).ok())
+                .collect::<Option<Vec<f64>>>();
+
+            if let Some(values) = values {
+                columns.push((name.as_str(), values));
+            }
+        }
+
+        let mut scores = FeatureScores::new(cache.key);
+
+        for (row, index) in indices.iter().enumerate() {
+            let values: Vec<(&str, f64)> = columns
+                .iter()
+                .map(|(name, values)| (*name, values[row]))
+                .collect();
+
+            scores.push(*index, &values)?;
+        }
+
+        scores.save(&cache.path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FeatureCache, FeatureScores};
+    use crate::{
+        model::{config::ModelConfig, description::ModelDescription, Model},
+        video::fixture,
+        vmaf::{error::FeatureCacheError, status::VmafStatus, Vmaf},
+    };
+
+    #[test]
+    fn save_and_load() {
+        let mut scores = FeatureScores::new(42);
+        scores.push(0, &[("a", 0.1), ("b", 1.0 / 3.0)]).unwrap();
+        scores.push(2, &[("a", 0.2), ("b", 2.0 / 3.0)]).unwrap();
+
+        // Out of order, and missing a feature
+        assert!(scores.push(1, &[("a", 0.3), ("b", 0.3)]).is_err());
+        assert!(scores.push(3, &[("a", 0.3)]).is_err());
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        scores.save(file.path()).unwrap();
+
+        let loaded = FeatureScores::load_keyed(file.path(), 42).unwrap();
+        assert_eq!(loaded, scores);
+        assert_eq!(loaded.get("b", 2), Some(2.0 / 3.0));
+        assert_eq!(loaded.get("b", 1), None);
+
+        let err = FeatureScores::load_keyed(file.path(), 7).unwrap_err();
+        assert!(matches!(
+            err.current_context(),
+            FeatureCacheError::Key {
+                expected: 7,
+                found: 42
+            }
+        ));
+
+        // Frames without any features
+        let mut empty = FeatureScores::new(42);
+        empty.push(0, &[]).unwrap();
+        empty.push(1, &[]).unwrap();
+        empty.save(file.path()).unwrap();
+        assert_eq!(FeatureScores::load(file.path()).unwrap(), empty);
+
+        std::fs::write(file.path(), "# libvmaf-rs feature scores\n").unwrap();
+        let err = FeatureScores::load(file.path()).unwrap_err();
+        assert!(matches!(
+            err.current_context(),
+            FeatureCacheError::Truncated
+        ));
+    }
+
+    #[test]
+    fn rescore() {
+        let reference = fixture::raw_video(5, 0);
+        let distorted = fixture::raw_video(5, 8);
+        let key = FeatureScores::hash_inputs([reference.path(), distorted.path()]).unwrap();
+
+        // The features of NEG models are named after the options of their extractors
+        for version in ["vmaf_v0.6.1", "vmaf_v0.6.1neg"] {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            let model = Model::new(ModelConfig::default(), version.to_string()).unwrap();
+
+            let scores = Vmaf::default()
+                .with_feature_cache(FeatureCache::new(file.path(), key))
+                .get_vmaf_scores(
+                    fixture::open(&reference),
+                    fixture::open(&distorted),
+                    model,
+                    None::<fn(VmafStatus)>,
+                )
+                .unwrap();
+
+            let cached = FeatureScores::load_keyed(file.path(), key).unwrap();
+            assert_eq!(cached.indices(), [0, 1, 2, 3, 4]);
+            assert!(!cached.names().iter().any(|name| name == "vmaf"));
+
+            let model = Model::new(ModelConfig::default(), version.to_string()).unwrap();
+            let rescored = cached.rescore(model).unwrap();
+
+            assert_eq!(rescored.len(), scores.len());
+            for (rescored, scored) in rescored.iter().zip(&scores) {
+                assert_eq!(rescored.index, scored.index);
+                assert!((rescored.score - scored.score).abs() < 1e-9);
+            }
+        }
+
+        // The features of a model loaded from JSON are saved under the names it looks them up by
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let model =
+            Model::load_model(ModelConfig::default(), "./examples/vmaf_v0.6.1.json").unwrap();
+        Vmaf::default()
+            .with_feature_cache(FeatureCache::new(file.path(), key))
+            .get_vmaf_scores(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model,
+                None::<fn(VmafStatus)>,
+            )
+            .unwrap();
+
+        let cached = FeatureScores::load(file.path()).unwrap();
+        let features = ModelDescription::from_path("./examples/vmaf_v0.6.1.json")
+            .unwrap()
+            .feature_names;
+        assert!(features
+            .iter()
+            .all(|feature| cached.names().contains(feature)));
+    }
+}
@@ -1,17 +1,20 @@
-use std::ptr;
+use std::{ffi::CStr, ptr};
 
 use crate::{error::FFIError, model::Model, picture::Picture};
 use error_stack::Result;
 use libvmaf_sys::{
-    vmaf_read_pictures, vmaf_score_at_index, vmaf_use_features_from_model, VmafModel, VmafPicture,
+    vmaf_feature_score_at_index, vmaf_import_feature_score, vmaf_read_pictures,
+    vmaf_score_at_index, vmaf_score_pooled, vmaf_use_feature, vmaf_use_features_from_model,
+    vmaf_write_output, VmafModel, VmafPicture,
 };
 use ptrplus::AsPtr;
 
-use super::Vmaf;
+use super::{output::OutputFormat, pooling::PoolingMethod, Vmaf};
 
 impl Vmaf {
     pub(super) fn use_features_from_model(&mut self, model: &Model) -> Result<(), FFIError> {
-        let err = unsafe { vmaf_use_features_from_model(self.0, model.as_ptr() as *mut VmafModel) };
+        let err =
+            unsafe { vmaf_use_features_from_model(self.ctx, model.as_ptr() as *mut VmafModel) };
 
         FFIError::check_err(err)
     }
@@ -23,7 +26,7 @@ impl Vmaf {
     ) -> Result<(), FFIError> {
         let err = unsafe {
             vmaf_read_pictures(
-                self.0,
+                self.ctx,
                 reference.as_ptr() as *mut VmafPicture,
                 distorted.as_ptr() as *mut VmafPicture,
                 index,
@@ -41,7 +44,7 @@ impl Vmaf {
 
     pub(super) fn finish_reading_pictures(&mut self) -> Result<(), FFIError> {
         let null: *mut VmafPicture = ptr::null_mut();
-        let err = unsafe { vmaf_read_pictures(self.0, null.clone(), null.clone(), 0) };
+        let err = unsafe { vmaf_read_pictures(self.ctx, null.clone(), null.clone(), 0) };
 
         FFIError::check_err(err)
     }
@@ -51,7 +54,7 @@ impl Vmaf {
 
         let err = unsafe {
             vmaf_score_at_index(
-                self.0,
+                self.ctx,
                 model.as_ptr() as *mut VmafModel,
                 &mut score as *mut f64,
                 index,
@@ -62,4 +65,72 @@ impl Vmaf {
 
         Ok(score)
     }
+
+    /// Pool the scores of frames `index_low` through `index_high`, both inclusive
+    pub(super) fn score_pooled(
+        &mut self,
+        model: &Model,
+        method: PoolingMethod,
+        index_low: u32,
+        index_high: u32,
+    ) -> Result<f64, FFIError> {
+        let mut score: f64 = 0.0;
+
+        let err = unsafe {
+            vmaf_score_pooled(
+                self.ctx,
+                model.as_ptr() as *mut VmafModel,
+                method.into(),
+                &mut score as *mut f64,
+                index_low,
+                index_high,
+            )
+        };
+
+        FFIError::check_err(err)?;
+
+        Ok(score)
+    }
+
+    pub(super) fn use_feature_by_name(&mut self, name: &CStr) -> Result<(), FFIError> {
+        let err = unsafe { vmaf_use_feature(self.ctx, name.as_ptr(), ptr::null_mut()) };
+
+        FFIError::check_err(err)
+    }
+
+    pub(super) fn import_feature_score_by_name(
+        &mut self,
+        name: &CStr,
+        index: u32,
+        value: f64,
+    ) -> Result<(), FFIError> {
+        let err = unsafe { vmaf_import_feature_score(self.ctx, name.as_ptr(), value, index) };
+
+        FFIError::check_err(err)
+    }
+
+    pub(super) fn feature_score_at_index(
+        &mut self,
+        name: &CStr,
+        index: u32,
+    ) -> Result<f64, FFIError> {
+        let mut score: f64 = 0.0;
+
+        let err =
+            unsafe { vmaf_feature_score_at_index(self.ctx, name.as_ptr(), &mut score, index) };
+
+        FFIError::check_err(err)?;
+
+        Ok(score)
+    }
+
+    pub(super) fn write_output(
+        &mut self,
+        path: &CStr,
+        format: OutputFormat,
+    ) -> Result<(), FFIError> {
+        let err = unsafe { vmaf_write_output(self.ctx, path.as_ptr(), format.into()) };
+
+        FFIError::check_err(err)
+    }
 }
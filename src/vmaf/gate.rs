@@ -0,0 +1,362 @@
+use error_stack::{Report, Result};
+use std::fmt::Display;
+
+use crate::{
+    model::Model,
+    picture::{error::PictureError, Picture, ValidRef},
+    video::resolution::GetResolution,
+};
+
+use super::{
+    config::VmafConfig, error::VmafError, pooling::PoolingMethod, score::FrameScore,
+    stats::Statistics, status::VmafStatus,
+};
+
+/// Pass/fail criteria on VMAF scores, for gating encoder changes in CI
+///
+/// Every criterion is checked, so a failing [`Verdict`] lists everything that went wrong rather than just the first thing
+/// ```ignore
+/// let verdict = QualityGate::new()
+///     .min_mean(93.0)
+///     .min_percentile(5.0, 85.0)
+///     .max_frames_below(70.0, 0)
+///     .run(reference, distorted, model)?;
+///
+/// assert!(verdict.passed(), "{verdict}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QualityGate {
+    config: VmafConfig,
+    min_mean: Option<f64>,
+    min_percentiles: Vec<(f64, f64)>,
+    max_frames_below: Vec<(f64, usize)>,
+    /// Criteria which were invalid when they were set, and so always fail
+    invalid: Vec<Violation>,
+}
+
+/// A single criterion of a [`QualityGate`] which wasn't met
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation {
+    /// Nothing was scored, so no criterion could be checked
+    NoScores,
+    /// The mean score was below `min`
+    Mean { min: f64, actual: f64 },
+    /// The `percentile`th percentile score was below `min`
+    Percentile {
+        percentile: f64,
+        min: f64,
+        actual: f64,
+    },
+    /// The `percentile`th percentile score couldn't be computed, such as when a frame scored NaN
+    MissingPercentile { percentile: f64, min: f64 },
+    /// A percentile given to [`QualityGate::min_percentile`] which isn't between 0 and 100
+    InvalidPercentile { percentile: f64 },
+    /// More than `max` frames scored below `threshold`
+    FramesBelow {
+        threshold: f64,
+        max: usize,
+        actual: usize,
+    },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::NoScores => write!(f, "no frames were scored"),
+            Violation::Mean { min, actual } => {
+                write!(f, "mean score {actual:.3} is below {min}")
+            }
+            Violation::Percentile {
+                percentile,
+                min,
+                actual,
+            } => write!(
+                f,
+                "{percentile}th percentile score {actual:.3} is below {min}"
+            ),
+            Violation::MissingPercentile { percentile, min } => write!(
+                f,
+                "{percentile}th percentile score couldn't be compared to {min}"
+            ),
+            Violation::InvalidPercentile { percentile } => {
+                write!(f, "percentile {percentile} isn't between 0 and 100")
+            }
+            Violation::FramesBelow {
+                threshold,
+                max,
+                actual,
+            } => write!(
+                f,
+                "{actual} frames scored below {threshold}, at most {max} are allowed"
+            ),
+        }
+    }
+}
+
+/// Result of checking scores against a [`QualityGate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    /// Mean of the scores, if anything was scored
+    pub mean: Option<f64>,
+    /// Every criterion which wasn't met. Empty if the scores passed
+    pub violations: Vec<Violation>,
+}
+
+impl Verdict {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.passed() {
+            return write!(f, "Quality gate passed");
+        }
+
+        write!(f, "Quality gate failed:")?;
+        for violation in &self.violations {
+            write!(f, "\n - {violation}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl QualityGate {
+    /// A gate with no criteria, which passes any non-empty scores. An empty set of scores always fails
+    pub fn new() -> QualityGate {
+        QualityGate::default()
+    }
+
+    /// Configuration of the `Vmaf` context used by [`QualityGate::run`]
+    pub fn config(mut self, config: VmafConfig) -> QualityGate {
+        self.config = config;
+        self
+    }
+
+    /// Fail if the mean score is below `min`
+    pub fn min_mean(mut self, min: f64) -> QualityGate {
+        self.min_mean = Some(min);
+        self
+    }
+
+    /// Fail if the `percentile`th percentile score, between 0 and 100, is below `min`. May be given several times.
+    /// Any other `percentile` fails every check with [`Violation::InvalidPercentile`]
+    pub fn min_percentile(mut self, percentile: f64, min: f64) -> QualityGate {
+        if (0.0..=100.0).contains(&percentile) {
+            self.min_percentiles.push((percentile, min));
+        } else {
+            self.invalid
+                .push(Violation::InvalidPercentile { percentile });
+        }
+        self
+    }
+
+    /// Fail if more than `max` frames score below `threshold`. May be given several times
+    pub fn max_frames_below(mut self, threshold: f64, max: usize) -> QualityGate {
+        self.max_frames_below.push((threshold, max));
+        self
+    }
+
+    /// Check `scores` against every criterion
+    pub fn check(&self, scores: &[FrameScore]) -> Verdict {
+        let mean = PoolingMethod::Mean.pool(scores);
+
+        let Some(actual_mean) = mean else {
+            return Verdict {
+                mean,
+                violations: vec![Violation::NoScores],
+            };
+        };
+
+        // Percentiles and frame counts don't depend on frame rate
+        let stats = Statistics::new(scores, 1.0);
+        let mut violations = self.invalid.clone();
+
+        if let Some(min) = self.min_mean {
+            if actual_mean.is_nan() || actual_mean < min {
+                violations.push(Violation::Mean {
+                    min,
+                    actual: actual_mean,
+                });
+            }
+        }
+
+        for &(percentile, min) in &self.min_percentiles {
+            match stats
+                .percentile(percentile)
+                .filter(|actual| !actual.is_nan())
+            {
+                Some(actual) if actual < min => violations.push(Violation::Percentile {
+                    percentile,
+                    min,
+                    actual,
+                }),
+                Some(_) => {}
+                None => violations.push(Violation::MissingPercentile { percentile, min }),
+            }
+        }
+
+        for &(threshold, max) in &self.max_frames_below {
+            let actual = stats.frames_below(threshold);
+            if actual > max {
+                violations.push(Violation::FramesBelow {
+                    threshold,
+                    max,
+                    actual,
+                });
+            }
+        }
+
+        Verdict { mean, violations }
+    }
+
+    /// Score `distorted` against `reference` and check the scores against every criterion
+    pub fn run<
+        R: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>,
+        D: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>,
+    >(
+        &self,
+        reference: R,
+        distorted: D,
+        model: Model,
+    ) -> Result<Verdict, VmafError> {
+        let scores = self.config.build()?.get_vmaf_scores(
+            reference,
+            distorted,
+            model,
+            None::<fn(VmafStatus)>,
+        )?;
+
+        Ok(self.check(&scores))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{QualityGate, Violation};
+    use crate::{
+        model::{config::ModelConfig, Model},
+        video::fixture,
+        vmaf::{score::FrameScore, status::VmafStatus, Vmaf},
+    };
+
+    #[test]
+    fn check() {
+        let scores: Vec<FrameScore> = (0..)
+            .zip([95.0, 94.0, 60.0, 96.0, 95.0])
+            .map(|(index, score)| FrameScore::new(index, score))
+            .collect();
+
+        let gate = QualityGate::new()
+            .min_mean(90.0)
+            .min_percentile(0.0, 50.0)
+            .max_frames_below(70.0, 0);
+
+        let verdict = gate.check(&scores);
+        assert!(!verdict.passed());
+        assert_eq!(
+            verdict.violations,
+            vec![
+                Violation::Mean {
+                    min: 90.0,
+                    actual: 88.0
+                },
+                Violation::FramesBelow {
+                    threshold: 70.0,
+                    max: 0,
+                    actual: 1
+                }
+            ]
+        );
+        assert!(verdict
+            .to_string()
+            .contains("mean score 88.000 is below 90"));
+
+        assert!(QualityGate::new().check(&scores).passed());
+        assert_eq!(
+            QualityGate::new().check(&[]).violations,
+            vec![Violation::NoScores]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let scores: Vec<FrameScore> = (0..)
+            .zip([95.0, f64::NAN, 96.0])
+            .map(|(index, score)| FrameScore::new(index, score))
+            .collect();
+
+        // NaN sorts above every score, so only the highest percentiles are missing
+        let verdict = QualityGate::new()
+            .min_percentile(0.0, 90.0)
+            .min_percentile(100.0, 90.0)
+            .min_percentile(f64::NAN, 90.0)
+            .min_percentile(500.0, 90.0)
+            .check(&scores);
+
+        assert_eq!(verdict.violations.len(), 3);
+        assert!(matches!(
+            verdict.violations[0],
+            Violation::InvalidPercentile { percentile } if percentile.is_nan()
+        ));
+        assert_eq!(
+            verdict.violations[1..],
+            [
+                Violation::InvalidPercentile { percentile: 500.0 },
+                Violation::MissingPercentile {
+                    percentile: 100.0,
+                    min: 90.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn run() {
+        let model = || Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+
+        let reference = fixture::raw_video(5, 0);
+        let distorted = fixture::raw_video(5, 16);
+
+        let verdict = QualityGate::new()
+            .min_mean(90.0)
+            .run(
+                fixture::open(&reference),
+                fixture::open(&reference),
+                model(),
+            )
+            .unwrap();
+        assert!(verdict.passed(), "{verdict}");
+
+        // Scores are clipped to 100, so this can't pass
+        let gate = QualityGate::new()
+            .min_mean(101.0)
+            .min_percentile(50.0, 101.0)
+            .max_frames_below(101.0, 2);
+
+        let scores = Vmaf::default()
+            .get_vmaf_scores(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model(),
+                None::<fn(VmafStatus)>,
+            )
+            .unwrap();
+        let verdict = gate
+            .run(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model(),
+            )
+            .unwrap();
+
+        assert_eq!(verdict.violations.len(), 3);
+        assert_eq!(verdict, gate.check(&scores));
+    }
+}
@@ -0,0 +1,59 @@
+//! Translation between libvmaf's log levels and [`tracing`] levels.
+//!
+//! With the `tracing` feature enabled, `Vmaf` emits spans and events for each phase of score calculation:
+//! decoding and feature extraction of every frame pair, flushing the feature extractors, and retrieving scores.
+//! Events carry the index of the frame they concern and how long the phase took.
+//!
+//! libvmaf itself logs with `vfprintf` straight to stderr, and its C API provides no hook to redirect these messages.
+//! What can be done is to keep libvmaf's verbosity in line with the current subscriber using [`from_level_filter`]
+
+use libvmaf_sys::VmafLogLevel;
+use tracing::{level_filters::LevelFilter, Level};
+
+/// The `tracing` level equivalent to a libvmaf log level. `VMAF_LOG_LEVEL_NONE` has no equivalent
+pub fn to_tracing_level(level: VmafLogLevel) -> Option<Level> {
+    match level {
+        VmafLogLevel::VMAF_LOG_LEVEL_NONE => None,
+        VmafLogLevel::VMAF_LOG_LEVEL_ERROR => Some(Level::ERROR),
+        VmafLogLevel::VMAF_LOG_LEVEL_WARNING => Some(Level::WARN),
+        VmafLogLevel::VMAF_LOG_LEVEL_INFO => Some(Level::INFO),
+        VmafLogLevel::VMAF_LOG_LEVEL_DEBUG => Some(Level::DEBUG),
+    }
+}
+
+/// The most verbose libvmaf log level allowed by `filter`. libvmaf has no trace level, so `TRACE` maps to debug
+pub fn from_level_filter(filter: LevelFilter) -> VmafLogLevel {
+    match filter.into_level() {
+        None => VmafLogLevel::VMAF_LOG_LEVEL_NONE,
+        Some(Level::ERROR) => VmafLogLevel::VMAF_LOG_LEVEL_ERROR,
+        Some(Level::WARN) => VmafLogLevel::VMAF_LOG_LEVEL_WARNING,
+        Some(Level::INFO) => VmafLogLevel::VMAF_LOG_LEVEL_INFO,
+        Some(_) => VmafLogLevel::VMAF_LOG_LEVEL_DEBUG,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use libvmaf_sys::VmafLogLevel;
+    use tracing::{level_filters::LevelFilter, Level};
+
+    use super::{from_level_filter, to_tracing_level};
+
+    #[test]
+    fn levels() {
+        assert_eq!(
+            to_tracing_level(VmafLogLevel::VMAF_LOG_LEVEL_WARNING),
+            Some(Level::WARN)
+        );
+        assert_eq!(to_tracing_level(VmafLogLevel::VMAF_LOG_LEVEL_NONE), None);
+
+        assert_eq!(
+            from_level_filter(LevelFilter::TRACE),
+            VmafLogLevel::VMAF_LOG_LEVEL_DEBUG
+        );
+        assert_eq!(
+            from_level_filter(LevelFilter::OFF),
+            VmafLogLevel::VMAF_LOG_LEVEL_NONE
+        );
+    }
+}
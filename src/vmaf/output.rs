@@ -0,0 +1,22 @@
+use libvmaf_sys::VmafOutputFormat;
+
+/// File formats libvmaf can write its report in. These mirror libvmaf's `VmafOutputFormat`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Xml,
+    Json,
+    Csv,
+    /// Subtitles, with the score of every frame shown while it is on screen
+    Sub,
+}
+
+impl From<OutputFormat> for VmafOutputFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Xml => VmafOutputFormat::VMAF_OUTPUT_FORMAT_XML,
+            OutputFormat::Json => VmafOutputFormat::VMAF_OUTPUT_FORMAT_JSON,
+            OutputFormat::Csv => VmafOutputFormat::VMAF_OUTPUT_FORMAT_CSV,
+            OutputFormat::Sub => VmafOutputFormat::VMAF_OUTPUT_FORMAT_SUB,
+        }
+    }
+}
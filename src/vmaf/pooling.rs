@@ -0,0 +1,69 @@
+use libvmaf_sys::VmafPoolingMethod;
+
+use super::score::FrameScore;
+
+/// Methods of pooling per-frame scores into a single score. These mirror libvmaf's `VmafPoolingMethod`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolingMethod {
+    Min,
+    Max,
+    Mean,
+    HarmonicMean,
+}
+
+impl PoolingMethod {
+    /// Pool `scores` into a single score, or `None` if `scores` is empty. See [`FrameScore`] for how subsampled runs are pooled
+    pub fn pool(&self, scores: &[FrameScore]) -> Option<f64> {
+        if scores.is_empty() {
+            return None;
+        }
+
+        let n = scores.len() as f64;
+        let scores = scores.iter().map(|frame| frame.score);
+
+        Some(match self {
+            PoolingMethod::Min => scores.fold(f64::INFINITY, f64::min),
+            PoolingMethod::Max => scores.fold(f64::NEG_INFINITY, f64::max),
+            PoolingMethod::Mean => scores.sum::<f64>() / n,
+            // Same as libvmaf, offset by one so that scores of 0 don't blow up
+            PoolingMethod::HarmonicMean => {
+                n / scores.map(|score| 1.0 / (score + 1.0)).sum::<f64>() - 1.0
+            }
+        })
+    }
+}
+
+impl From<PoolingMethod> for VmafPoolingMethod {
+    fn from(method: PoolingMethod) -> Self {
+        match method {
+            PoolingMethod::Min => VmafPoolingMethod::VMAF_POOL_METHOD_MIN,
+            PoolingMethod::Max => VmafPoolingMethod::VMAF_POOL_METHOD_MAX,
+            PoolingMethod::Mean => VmafPoolingMethod::VMAF_POOL_METHOD_MEAN,
+            PoolingMethod::HarmonicMean => VmafPoolingMethod::VMAF_POOL_METHOD_HARMONIC_MEAN,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PoolingMethod;
+    use crate::vmaf::score::FrameScore;
+
+    #[test]
+    fn pool() {
+        // Subsampled by 2
+        let scores: Vec<FrameScore> = [(0, 80.0), (2, 90.0), (4, 100.0)]
+            .into_iter()
+            .map(|(index, score)| FrameScore::new(index, score))
+            .collect();
+
+        assert_eq!(PoolingMethod::Min.pool(&scores), Some(80.0));
+        assert_eq!(PoolingMethod::Max.pool(&scores), Some(100.0));
+        assert_eq!(PoolingMethod::Mean.pool(&scores), Some(90.0));
+
+        let harmonic = PoolingMethod::HarmonicMean.pool(&scores).unwrap();
+        assert!(harmonic < 90.0 && harmonic > 80.0);
+
+        assert_eq!(PoolingMethod::Mean.pool(&[]), None);
+    }
+}
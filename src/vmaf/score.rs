@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+/// The VMAF score of a single frame pair
+///
+/// When a `Vmaf` context subsamples, only every `n_subsample`th frame is scored, so `index` is the
+/// position of the frame pair in decode order rather than its position in the list of scores.
+/// Skipped frames have no score at all, so pooling and statistics over a subsampled run only take the frames which were actually scored into account
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameScore {
+    pub index: u32,
+    pub score: f64,
+    /// Presentation timestamp of the reference frame, in units of the reference's time base
+    pub pts: Option<i64>,
+    /// Presentation timestamp of the reference frame, since the start of the reference.
+    /// `None` if the reference has no timestamps, or no time base was given with `Vmaf::with_time_base()`
+    pub timestamp: Option<Duration>,
+}
+
+impl FrameScore {
+    /// A score with no timestamp
+    pub fn new(index: u32, score: f64) -> FrameScore {
+        FrameScore {
+            index,
+            score,
+            pts: None,
+            timestamp: None,
+        }
+    }
+}
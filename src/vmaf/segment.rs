@@ -0,0 +1,217 @@
+use error_stack::{Report, Result, ResultExt};
+use std::{fmt::Display, ops::Range};
+
+use crate::{
+    model::Model,
+    picture::{error::PictureError, Picture, ValidRef},
+    video::resolution::GetResolution,
+};
+
+use super::{
+    error::VmafError, pooling::PoolingMethod, score::FrameScore, status::VmafStatus, Vmaf,
+};
+
+/// A run of consecutive frames, such as a shot. `start` is inclusive and `end` is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Segment {
+    pub fn new(start: u32, end: u32) -> Segment {
+        Segment { start, end }
+    }
+
+    /// Split `frames` frames into segments, starting a new segment at every index in `cuts`.
+    /// Cuts which are out of order, duplicated, at frame 0 or past the last frame are ignored
+    pub fn from_cuts(cuts: &[u32], frames: u32) -> Vec<Segment> {
+        let mut segments = vec![];
+        let mut start = 0;
+
+        for &cut in cuts {
+            if cut > start && cut < frames {
+                segments.push(Segment::new(start, cut));
+                start = cut;
+            }
+        }
+
+        if start < frames {
+            segments.push(Segment::new(start, frames));
+        }
+
+        segments
+    }
+
+    /// Number of frames in this segment
+    pub fn len(&self) -> u32 {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, index: u32) -> bool {
+        (self.start..self.end).contains(&index)
+    }
+}
+
+impl From<Range<u32>> for Segment {
+    fn from(range: Range<u32>) -> Self {
+        Segment::new(range.start, range.end)
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Scores of a single [`Segment`], pooled by libvmaf
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentScore {
+    pub segment: Segment,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub harmonic_mean: f64,
+}
+
+impl SegmentScore {
+    /// The pooled score for `method`
+    pub fn get(&self, method: PoolingMethod) -> f64 {
+        match method {
+            PoolingMethod::Min => self.min,
+            PoolingMethod::Max => self.max,
+            PoolingMethod::Mean => self.mean,
+            PoolingMethod::HarmonicMean => self.harmonic_mean,
+        }
+    }
+}
+
+impl Vmaf {
+    /// Same as `get_vmaf_scores()`, but also pools the scores of every segment in `segments`, such as the shots of a title.
+    ///
+    /// Returns the per-frame scores along with one [`SegmentScore`] per segment, in the order they were given.
+    /// Every segment must be non-empty and within the frames that were read, otherwise [`VmafError::Segment`] is returned.
+    /// If there's no shot list at hand, [`SceneDetect`](crate::video::scenedetect::SceneDetect) can find the cuts in the reference
+    pub fn get_segment_scores<
+        R: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>,
+        D: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>,
+        F: FnMut(VmafStatus),
+    >(
+        mut self,
+        reference: R,
+        distorted: D,
+        model: Model,
+        segments: &[Segment],
+        callback: Option<F>,
+    ) -> Result<(Vec<FrameScore>, Vec<SegmentScore>), VmafError> {
+        let scores = self.score_frames(reference, distorted, &model, callback)?;
+
+        // Frames after the last scored frame are skipped by libvmaf's pooling anyways when subsampling
+        let frames = scores
+            .last()
+            .map_or(0, |last| last.index + self.config.n_subsample.max(1));
+
+        let mut segment_scores = vec![];
+
+        for segment in segments {
+            if segment.is_empty() || segment.end > frames {
+                return Err(Report::new(VmafError::Segment(*segment)));
+            }
+
+            let mut pool = |method: PoolingMethod| {
+                self.score_pooled(&model, method, segment.start, segment.end - 1)
+                    .change_context(VmafError::Segment(*segment))
+            };
+
+            segment_scores.push(SegmentScore {
+                segment: *segment,
+                min: pool(PoolingMethod::Min)?,
+                max: pool(PoolingMethod::Max)?,
+                mean: pool(PoolingMethod::Mean)?,
+                harmonic_mean: pool(PoolingMethod::HarmonicMean)?,
+            });
+        }
+
+        Ok((scores, segment_scores))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Segment;
+    use crate::{
+        model::{config::ModelConfig, Model},
+        video::fixture,
+        vmaf::{error::VmafError, pooling::PoolingMethod, status::VmafStatus, Vmaf},
+    };
+
+    #[test]
+    fn from_cuts() {
+        assert_eq!(
+            Segment::from_cuts(&[0, 10, 10, 5, 25, 40], 30),
+            vec![
+                Segment::new(0, 10),
+                Segment::new(10, 25),
+                Segment::new(25, 30)
+            ]
+        );
+        assert!(Segment::from_cuts(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn segment_scores() {
+        let model = || Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+
+        let reference = fixture::raw_video(6, 0);
+        let distorted = fixture::raw_video(6, 8);
+        let segments = Segment::from_cuts(&[2], 6);
+
+        let (scores, segment_scores) = Vmaf::default()
+            .get_segment_scores(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model(),
+                &segments,
+                None::<fn(VmafStatus)>,
+            )
+            .unwrap();
+
+        assert_eq!(scores.len(), 6);
+        assert_eq!(segment_scores.len(), 2);
+
+        for (segment_score, frames) in segment_scores.iter().zip([&scores[..2], &scores[2..]]) {
+            let min = PoolingMethod::Min.pool(frames).unwrap();
+            let max = PoolingMethod::Max.pool(frames).unwrap();
+            let mean = PoolingMethod::Mean.pool(frames).unwrap();
+
+            assert!((segment_score.min - min).abs() < 1e-6);
+            assert!((segment_score.max - max).abs() < 1e-6);
+            assert!((segment_score.mean - mean).abs() < 1e-6);
+        }
+
+        // A segment which runs past the last frame
+        let err = Vmaf::default()
+            .get_segment_scores(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model(),
+                &[Segment::new(4, 8)],
+                None::<fn(VmafStatus)>,
+            )
+            .unwrap_err();
+
+        assert!(matches!(
+            err.current_context(),
+            VmafError::Segment(segment) if *segment == Segment::new(4, 8)
+        ));
+    }
+}
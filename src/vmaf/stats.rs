@@ -0,0 +1,220 @@
+use std::{collections::VecDeque, time::Duration};
+
+use super::score::FrameScore;
+
+/// Statistics over per-frame scores which show short dips in quality that a pooled score hides
+///
+/// Durations are converted to a number of frames with `frame_rate`. Windows are measured in frames rather than in scores,
+/// which matters for subsampled runs, see [`FrameScore`]
+/// ```ignore
+/// let stats = Statistics::new(&scores, 24.0);
+/// let p5 = stats.percentile(5.0);
+/// let worst = stats.worst_segment(Duration::from_secs(2));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Statistics<'a> {
+    scores: &'a [FrameScore],
+    frame_rate: f64,
+}
+
+/// The window of frames with the lowest mean score, as found by [`Statistics::worst_segment`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorstSegment {
+    /// Index of the first frame of the segment
+    pub start: u32,
+    /// Index of the frame after the last frame of the segment
+    pub end: u32,
+    /// Mean score of the frames in the segment
+    pub mean: f64,
+}
+
+impl<'a> Statistics<'a> {
+    /// `scores` must be in ascending order of index, as returned by `Vmaf::get_vmaf_scores()`
+    pub fn new(scores: &'a [FrameScore], frame_rate: f64) -> Statistics<'a> {
+        Statistics { scores, frame_rate }
+    }
+
+    /// The `p`th percentile of the scores, between 0 and 100, interpolating linearly between the closest ranks.
+    /// Returns `None` if there are no scores
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        if self.scores.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<f64> = self.scores.iter().map(|frame| frame.score).collect();
+        sorted.sort_by(f64::total_cmp);
+
+        let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+        let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+
+        Some(sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64))
+    }
+
+    /// Number of frames which scored below `threshold`
+    pub fn frames_below(&self, threshold: f64) -> usize {
+        self.scores
+            .iter()
+            .filter(|frame| frame.score < threshold)
+            .count()
+    }
+
+    /// The lowest score in every window of `duration`, with one window starting at each scored frame.
+    /// Windows are considered the same way as by [`Statistics::worst_segment`], so this is empty if there are no scores
+    pub fn sliding_min(&self, duration: Duration) -> Vec<f64> {
+        let (first, last) = match (self.scores.first(), self.scores.last()) {
+            (Some(first), Some(last)) => (first.index, last.index),
+            _ => return vec![],
+        };
+
+        let window = self.window(duration);
+        let latest_start = (last + 1).saturating_sub(window).max(first);
+
+        let mut minimums = vec![];
+        // Positions of the scores which may still be the minimum of a window, with ascending scores
+        let mut candidates: VecDeque<usize> = VecDeque::new();
+        let mut end = 0;
+
+        for (start, frame) in self.scores.iter().enumerate() {
+            if frame.index > latest_start {
+                break;
+            }
+
+            let end_index = frame.index + window;
+            while end < self.scores.len() && self.scores[end].index < end_index {
+                let score = self.scores[end].score;
+                while candidates
+                    .back()
+                    .is_some_and(|back| self.scores[*back].score >= score)
+                {
+                    candidates.pop_back();
+                }
+                candidates.push_back(end);
+                end += 1;
+            }
+
+            while candidates.front().is_some_and(|front| *front < start) {
+                candidates.pop_front();
+            }
+
+            minimums.push(self.scores[candidates[0]].score);
+        }
+
+        minimums
+    }
+
+    /// The window of `duration` with the lowest mean score.
+    /// If the scores span less than `duration`, the segment covers all of them. Returns `None` if there are no scores
+    pub fn worst_segment(&self, duration: Duration) -> Option<WorstSegment> {
+        let first = self.scores.first()?.index;
+        let last = self.scores.last()?.index;
+
+        let window = self.window(duration);
+        // Windows which don't fit before the last frame aren't considered, unless none do
+        let latest_start = (last + 1).saturating_sub(window).max(first);
+
+        let mut worst: Option<WorstSegment> = None;
+        let mut end = 0;
+        let mut sum = 0.0;
+
+        for (start, frame) in self.scores.iter().enumerate() {
+            if frame.index > latest_start {
+                break;
+            }
+
+            let end_index = frame.index + window;
+            while end < self.scores.len() && self.scores[end].index < end_index {
+                sum += self.scores[end].score;
+                end += 1;
+            }
+
+            let mean = sum / (end - start) as f64;
+            if worst.is_none_or(|worst| mean < worst.mean) {
+                worst = Some(WorstSegment {
+                    start: frame.index,
+                    end: end_index.min(last + 1),
+                    mean,
+                });
+            }
+
+            sum -= frame.score;
+        }
+
+        worst
+    }
+
+    /// Number of frames in a window of `duration`
+    fn window(&self, duration: Duration) -> u32 {
+        ((duration.as_secs_f64() * self.frame_rate).round() as u32).max(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{Statistics, WorstSegment};
+    use crate::vmaf::score::FrameScore;
+
+    fn scores(scores: &[f64]) -> Vec<FrameScore> {
+        (0..)
+            .zip(scores)
+            .map(|(index, score)| FrameScore::new(index, *score))
+            .collect()
+    }
+
+    #[test]
+    fn percentile() {
+        let scores = scores(&[50.0, 10.0, 40.0, 20.0, 30.0]);
+        let stats = Statistics::new(&scores, 24.0);
+
+        assert_eq!(stats.percentile(0.0), Some(10.0));
+        assert_eq!(stats.percentile(50.0), Some(30.0));
+        assert_eq!(stats.percentile(100.0), Some(50.0));
+        assert_eq!(stats.percentile(10.0), Some(14.0));
+
+        assert_eq!(Statistics::new(&[], 24.0).percentile(5.0), None);
+    }
+
+    #[test]
+    fn frames_below() {
+        let scores = scores(&[95.0, 80.0, 60.0, 93.0]);
+        assert_eq!(Statistics::new(&scores, 24.0).frames_below(90.0), 2);
+    }
+
+    #[test]
+    fn worst_segment() {
+        let scores = scores(&[90.0, 90.0, 50.0, 60.0, 90.0, 90.0]);
+        // Two frames per window
+        let stats = Statistics::new(&scores, 2.0);
+
+        assert_eq!(
+            stats.worst_segment(Duration::from_secs(1)),
+            Some(WorstSegment {
+                start: 2,
+                end: 4,
+                mean: 55.0
+            })
+        );
+
+        // Longer than the video
+        let whole = stats.worst_segment(Duration::from_secs(10)).unwrap();
+        assert_eq!((whole.start, whole.end), (0, 6));
+    }
+
+    #[test]
+    fn sliding_min() {
+        let scores = scores(&[90.0, 70.0, 95.0, 50.0, 80.0, 85.0]);
+        let stats = Statistics::new(&scores, 3.0);
+
+        // Three frames per window
+        assert_eq!(
+            stats.sliding_min(Duration::from_secs(1)),
+            vec![70.0, 50.0, 50.0, 50.0]
+        );
+        // Longer than the video
+        assert_eq!(stats.sliding_min(Duration::from_secs(10)), vec![50.0]);
+        assert!(Statistics::new(&[], 24.0)
+            .sliding_min(Duration::from_secs(1))
+            .is_empty());
+    }
+}
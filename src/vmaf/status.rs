@@ -1,24 +1,56 @@
-/// This struct represents the status of VMAF calculation  
+use std::time::Duration;
+
+/// This struct represents the status of VMAF calculation
 ///
 /// For every frame pair decoded, a `Decode` variant is emitted to the callback function provided to `Vmaf::get_vmaf_scores()`
-/// After all frames are decoded, the `GetScore` variants are emitted to `Vmaf::get_vmaf_scores()`
+/// Once every frame pair is decoded, a single `Flush` variant is emitted after libvmaf's feature extractors are flushed.
+/// After that, a `GetScore` variant is emitted for every frame pair that is scored, and finally a single `Done` variant.
 ///
-/// ### Important!
-/// Given that the two [`Video`](../video/struct.Video.html) structs passed to `Vmaf::get_vmaf_scores()` have the same number of frames,
-///  the number of times each variant is emitted from `Vmaf::get_vmaf_scores()` is equal to the number of frame pairs.
-/// In this way, you may calculate the progress of Vmaf score calculation in this manner:
-/// `(# of times a variant has been emitted)/(number of frame pairs)`.
+/// Every variant carries `elapsed`, the time since `Vmaf::get_vmaf_scores()` started.
+/// Progress may be calculated directly from the variants, as `(index + 1)/total`:
+/// ```ignore
+/// let callback = |status: VmafStatus| match status {
+///     VmafStatus::Decode { index, total: Some(total), .. } => decode_progress.set_position(index as u64 + 1),
+///     VmafStatus::GetScore { score, .. } => scores_so_far.push(score),
+///     _ => (),
+/// };
+/// ```
 /// One may intuit that the progress of vmaf score calculation occurs in two stages,
 /// Decoding, and Retrieving the score. Ideally this should be represented in two seperate progress bars
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum VmafStatus {
     /// update on the decoding of a video framepair.
     /// Every time a frame pair is decoded and processed, this variant is emitted
     /// to the callback function provided to `Vmaf::get_vmaf_scores()`
-    Decode,
+    Decode {
+        /// Index of the frame pair, in decode order
+        index: u32,
+        /// Number of frame pairs, if the inputs know how many frames they have
+        total: Option<usize>,
+        elapsed: Duration,
+    },
+    /// Every frame pair has been decoded, and libvmaf's feature extractors have been flushed
+    Flush { elapsed: Duration },
     /// this variant is an update on the retrieval of a Vmaf Score after all
     /// frames are decoded and processed.
     /// After all frames are decoded, this variant is emitted to the callback function provided to
     ///`Vmaf::get_vmaf_scores()`
-    GetScore,
-}
\ No newline at end of file
+    GetScore {
+        /// Index of the frame pair this score belongs to, in decode order
+        index: u32,
+        /// Number of frame pairs that will be scored. This is fewer than the number of frame pairs if the `Vmaf` context subsamples
+        total: Option<usize>,
+        score: f64,
+        /// Presentation timestamp of the reference frame, in units of the reference's time base
+        pts: Option<i64>,
+        /// Presentation timestamp of the reference frame, since the start of the reference
+        timestamp: Option<Duration>,
+        elapsed: Duration,
+    },
+    /// Every score has been retrieved
+    Done {
+        /// Number of frame pairs which were scored
+        scored: usize,
+        elapsed: Duration,
+    },
+}
@@ -0,0 +1,139 @@
+use error_stack::{Report, Result};
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    task::JoinHandle,
+};
+
+use crate::{
+    model::Model,
+    picture::{error::PictureError, Picture, ValidRef},
+    video::resolution::GetResolution,
+};
+
+use super::{error::VmafError, score::FrameScore, status::VmafStatus, Vmaf};
+
+/// A handle to a `get_vmaf_scores()` job running on tokio's blocking thread pool
+///
+/// Status updates, including the score of every frame, are received with [`ScoreStream::next`] as they happen.
+/// Dropping this handle doesn't stop the job. Give the `Vmaf` context a [`CancelToken`](super::cancel::CancelToken) for that
+pub struct ScoreStream {
+    events: UnboundedReceiver<VmafStatus>,
+    handle: JoinHandle<Result<Vec<FrameScore>, VmafError>>,
+}
+
+impl ScoreStream {
+    /// The next status update, or `None` once the job has finished and every update has been received
+    pub async fn next(&mut self) -> Option<VmafStatus> {
+        self.events.recv().await
+    }
+
+    /// The next per-frame score, skipping every other kind of status update
+    pub async fn next_score(&mut self) -> Option<FrameScore> {
+        loop {
+            if let VmafStatus::GetScore {
+                index,
+                score,
+                pts,
+                timestamp,
+                ..
+            } = self.next().await?
+            {
+                return Some(FrameScore {
+                    index,
+                    score,
+                    pts,
+                    timestamp,
+                });
+            }
+        }
+    }
+
+    /// Wait for the job to finish, and get every score
+    pub async fn finish(self) -> Result<Vec<FrameScore>, VmafError> {
+        match self.handle.await {
+            Ok(result) => result,
+            Err(e) => Err(Report::new(e).change_context(VmafError::Other)),
+        }
+    }
+}
+
+impl Vmaf {
+    /// Run `get_vmaf_scores()` on tokio's blocking thread pool, and stream its progress back.
+    /// Must be called from within a tokio runtime
+    pub fn score_stream<
+        R: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>
+            + Send
+            + 'static,
+        D: GetResolution
+            + ExactSizeIterator
+            + Iterator<Item = impl TryInto<Picture<ValidRef>, Error = Report<PictureError>>>
+            + Send
+            + 'static,
+    >(
+        self,
+        reference: R,
+        distorted: D,
+        model: Model,
+    ) -> ScoreStream {
+        let (sender, events) = unbounded_channel();
+
+        let handle = tokio::task::spawn_blocking(move || {
+            // The receiver going away just means nobody is listening anymore
+            let callback = move |status: VmafStatus| {
+                let _ = sender.send(status);
+            };
+
+            self.get_vmaf_scores(reference, distorted, model, Some(callback))
+        });
+
+        ScoreStream { events, handle }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        model::{config::ModelConfig, Model},
+        video::fixture,
+        vmaf::{status::VmafStatus, Vmaf},
+    };
+
+    #[test]
+    fn stream() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let model = || Model::new(ModelConfig::default(), "vmaf_v0.6.1".to_string()).unwrap();
+        let reference = fixture::raw_video(5, 0);
+        let distorted = fixture::raw_video(5, 8);
+
+        let direct = Vmaf::default()
+            .get_vmaf_scores(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model(),
+                None::<fn(VmafStatus)>,
+            )
+            .unwrap();
+
+        runtime.block_on(async {
+            let mut stream = Vmaf::default().score_stream(
+                fixture::open(&reference),
+                fixture::open(&distorted),
+                model(),
+            );
+
+            let mut streamed = vec![];
+            while let Some(score) = stream.next_score().await {
+                streamed.push(score);
+            }
+
+            let scores = stream.finish().await.unwrap();
+            assert_eq!(streamed, scores);
+            assert_eq!(scores, direct);
+        });
+    }
+}